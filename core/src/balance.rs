@@ -0,0 +1,81 @@
+use crate::geometry::{Coordinates, Dimensions};
+use crate::packing::{Packing, Placement, Space};
+
+/// The weighted mean of the centres of `placements`, `None` if they weigh
+/// nothing.
+pub(crate) fn centre_of_gravity(placements: &[Placement]) -> Option<Coordinates> {
+    let mut moment = [0.0; 3];
+    let mut weight = 0.0;
+    for placement in placements {
+        let w = placement.weight.unwrap_or(0.0);
+        for (axis, m) in moment.iter_mut().enumerate() {
+            *m += w * (placement.position[axis] + placement.dimensions[axis] / 2.0);
+        }
+        weight += w;
+    }
+    if weight == 0.0 {
+        return None;
+    }
+    Some([moment[0] / weight, moment[1] / weight, moment[2] / weight])
+}
+
+/// How far the whole of `placements` can slide along the container's length
+/// and width to bring their centre of gravity as close to the container's
+/// centre as the walls allow.
+fn centring_shift(placements: &[Placement], bounds: &Coordinates) -> Coordinates {
+    let centre = match centre_of_gravity(placements) {
+        Some(centre) => centre,
+        None => return [0.0; 3],
+    };
+    let mut shift = [0.0; 3];
+    for (axis, s) in shift.iter_mut().enumerate().take(2) {
+        let low = placements.iter().map(|p| p.position[axis]).fold(f64::INFINITY, f64::min);
+        let high = placements.iter().map(|p| p.position[axis] + p.dimensions[axis]).fold(f64::NEG_INFINITY, f64::max);
+        // not clamp, as rounding may leave the load a hair wider than the container
+        *s = (bounds[axis] / 2.0 - centre[axis]).min(bounds[axis] - high).max(-low);
+    }
+    shift
+}
+
+/// Whether `placements`, once slid to the centre, have their centre of
+/// gravity within `envelope` of the container's centre along every axis.
+pub(crate) fn balanced(placements: &[Placement], bounds: &Coordinates, envelope: &Coordinates) -> bool {
+    let centre = match centre_of_gravity(placements) {
+        Some(centre) => centre,
+        None => return true,
+    };
+    let shift = centring_shift(placements, bounds);
+    (0..3).all(|axis| (centre[axis] + shift[axis] - bounds[axis] / 2.0).abs() <= envelope[axis] + 1e-9)
+}
+
+/// Slides everything in `packing` to bring its centre of gravity as close to
+/// the container's centre as the walls allow. Free spaces move along and are
+/// cut back to the container.
+pub(crate) fn centre_load(packing: &mut Packing, bounds: &Coordinates) {
+    let shift = centring_shift(&packing.placements, bounds);
+    if shift == [0.0; 3] {
+        return;
+    }
+    for placement in &mut packing.placements {
+        for (axis, s) in shift.iter().enumerate() {
+            placement.position[axis] += s;
+        }
+    }
+    packing.spaces = packing
+        .spaces
+        .iter()
+        .filter_map(|space| {
+            let mut position = space.position;
+            let mut end = [0.0; 3];
+            for axis in 0..3 {
+                position[axis] = (space.position[axis] + shift[axis]).max(0.0);
+                end[axis] = (space.position[axis] + space.dimensions[axis] + shift[axis]).min(bounds[axis]);
+            }
+            let extents = [end[0] - position[0], end[1] - position[1], end[2] - position[2]];
+            if extents.iter().any(|&e| e <= 0.0) {
+                return None;
+            }
+            Some(Space { dimensions: Dimensions::from_array(&extents), position })
+        })
+        .collect();
+}
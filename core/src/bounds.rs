@@ -0,0 +1,118 @@
+use crate::item::{Container, Item};
+use crate::place::{allowed_rotations, rotate};
+
+/// Lower bounds on the number of containers any packing of the items needs.
+///
+/// Only items that fit into an empty container on their own are counted, the
+/// others end up in [`PackResult::errors`](crate::PackResult::errors) anyway.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LowerBounds {
+    /// Total item volume over the container volume, rounded up.
+    pub volume: usize,
+    /// Size of a set of items no two of which fit into one container together,
+    /// in the spirit of Martello, Pisinger and Vigo's L1.
+    pub l1: usize,
+    /// [`l1`](Self::l1) plus the containers needed for the volume that does
+    /// not fit beside those items, in the spirit of their L2.
+    pub l2: usize,
+    /// Total item weight over the weight limit, rounded up.
+    pub weight: usize,
+}
+
+impl LowerBounds {
+    /// The tightest of the bounds.
+    pub fn best(&self) -> usize {
+        self.volume.max(self.l1).max(self.l2).max(self.weight)
+    }
+
+    /// How far `packings` containers are above [`best`](Self::best), as a
+    /// fraction of it. Zero when the bound is met.
+    pub fn gap(&self, packings: usize) -> f64 {
+        let best = self.best();
+        if best == 0 {
+            return 0.0;
+        }
+        packings.saturating_sub(best) as f64 / best as f64
+    }
+}
+
+pub(crate) fn ceil_div(total: f64, capacity: f64) -> usize {
+    if total <= 0.0 {
+        return 0;
+    }
+    // don't let rounding errors push an exact fit up to the next container
+    (total / capacity - 1e-9).ceil() as usize
+}
+
+fn bounds(container: &Container) -> [f64; 3] {
+    container.dimensions.dimensions
+}
+
+/// The extents of every allowed rotation of `item` that fits `container`.
+fn fitting_extents(container: &Container, item: &Item) -> Vec<[f64; 3]> {
+    let bounds = bounds(container);
+    allowed_rotations(item)
+        .iter()
+        .map(|rotation| rotate(&item.dimensions, rotation))
+        .filter(|extents| (0..3).all(|axis| extents[axis] <= bounds[axis]))
+        .collect()
+}
+
+pub(crate) fn fits_alone(container: &Container, item: &Item) -> bool {
+    container.allows(item.weight.unwrap_or(0.0)) && !fitting_extents(container, item).is_empty()
+}
+
+/// Whether `a` and `b` could possibly share a container: their weights
+/// together are allowed and some rotations put them side by side along one
+/// axis. Necessary but not sufficient, which is all a bound needs.
+fn can_share(container: &Container, a: &Item, b: &Item) -> bool {
+    if !container.allows(a.weight.unwrap_or(0.0) + b.weight.unwrap_or(0.0)) {
+        return false;
+    }
+    let bounds = bounds(container);
+    let (a_extents, b_extents) = (fitting_extents(container, a), fitting_extents(container, b));
+    a_extents
+        .iter()
+        .any(|ea| b_extents.iter().any(|eb| (0..3).any(|axis| ea[axis] + eb[axis] <= bounds[axis])))
+}
+
+/// Computes the [`LowerBounds`] for packing `items` into copies of
+/// `container`.
+pub fn lower_bounds(container: &Container, items: &[Item]) -> LowerBounds {
+    let mut items: Vec<&Item> = items.iter().filter(|item| fits_alone(container, item)).collect();
+    let capacity = container.dimensions.volume();
+    let total_volume: f64 = items.iter().map(|item| item.dimensions.volume()).sum();
+    let total_weight: f64 = items.iter().map(|item| item.weight.unwrap_or(0.0)).sum();
+
+    // biggest first makes a large set of mutually exclusive items likely
+    items.sort_by(|a, b| b.dimensions.volume().total_cmp(&a.dimensions.volume()));
+    let mut large: Vec<&Item> = Vec::new();
+    let mut rest: Vec<&Item> = Vec::new();
+    for item in items {
+        if large.iter().all(|other| !can_share(container, item, other)) {
+            large.push(item);
+        } else {
+            rest.push(item);
+        }
+    }
+
+    // the large items each need a container of their own, the rest can at
+    // best fill the room left beside those they can share a container with
+    let usable_room: f64 = large
+        .iter()
+        .map(|item| {
+            let joining: f64 =
+                rest.iter().filter(|other| can_share(container, item, other)).map(|other| other.dimensions.volume()).sum();
+            (capacity - item.dimensions.volume()).min(joining)
+        })
+        .sum();
+    let rest_volume: f64 = rest.iter().map(|item| item.dimensions.volume()).sum();
+    let l2 = large.len() + ceil_div(rest_volume - usable_room, capacity);
+
+    LowerBounds {
+        volume: ceil_div(total_volume, capacity),
+        l1: large.len(),
+        l2,
+        weight: container.payload_limit().map_or(0, |limit| ceil_div(total_weight, limit)),
+    }
+}
@@ -0,0 +1,241 @@
+use crate::container_types::{fill_strategy, fill_with};
+use crate::error::{self, PackError};
+use crate::item::{Container, Item};
+use crate::options::{PackOptions, Strategy};
+use crate::pack_with_options;
+use crate::packing::{PackResult, Packing};
+use crate::unpacked::{Reason, Unpacked};
+use std::cmp::Ordering;
+
+/// A weight break of a [`Carrier`]'s rate table: what a parcel of up to
+/// `max_weight` billable weight costs to ship.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rate {
+    pub max_weight: f64,
+    pub cost: f64,
+}
+
+/// How a carrier bills parcels: by the greater of their gross weight and
+/// their outer volume over `dim_divisor`.
+#[derive(Clone, Debug)]
+pub struct Carrier {
+    pub dim_divisor: f64,
+    /// Weight breaks, in any order. Without any the billable weight itself
+    /// is what a parcel costs.
+    pub rates: Vec<Rate>,
+}
+
+impl Carrier {
+    pub fn new(dim_divisor: f64) -> Carrier {
+        Carrier { dim_divisor, rates: vec![] }
+    }
+
+    pub fn with_rates(mut self, rates: Vec<Rate>) -> Carrier {
+        self.rates = rates;
+        self
+    }
+
+    /// The weight `container` is billed at when its contents weigh `weight`.
+    pub fn billable_weight(&self, container: &Container, weight: f64) -> f64 {
+        (container.tare_weight + weight).max(container.outer_dimensions.volume() / self.dim_divisor)
+    }
+
+    /// What shipping a parcel of `billable_weight` costs, `None` if it is
+    /// beyond the last weight break.
+    pub fn charge(&self, billable_weight: f64) -> Option<f64> {
+        if self.rates.is_empty() {
+            return Some(billable_weight);
+        }
+        self.rates
+            .iter()
+            .filter(|rate| billable_weight <= rate.max_weight + 1e-9)
+            .min_by(|a, b| a.max_weight.total_cmp(&b.max_weight))
+            .map(|rate| rate.cost)
+    }
+
+    fn charge_for(&self, container: &Container, weight: f64) -> Option<f64> {
+        self.charge(self.billable_weight(container, weight))
+    }
+}
+
+/// A container of the catalog and what went into it.
+struct Parcel {
+    container: usize,
+    packing: Packing,
+    items: Vec<Item>,
+}
+
+/// A way of shipping the items.
+struct Shipment {
+    packings: Vec<Packing>,
+    unplaced: Vec<Item>,
+    cost: f64,
+    /// The strategy the packings were made with.
+    strategy: Strategy,
+}
+
+impl Shipment {
+    /// Fewer items left behind first, then lower cost, then fewer and then
+    /// smaller containers.
+    fn is_better_than(&self, other: &Shipment) -> bool {
+        let key = |shipment: &Shipment| {
+            let volume: f64 = shipment.packings.iter().map(|packing| packing.volume).sum();
+            (shipment.unplaced.len(), shipment.cost, shipment.packings.len(), volume)
+        };
+        key(self).partial_cmp(&key(other)) == Some(Ordering::Less)
+    }
+}
+
+/// Packs every item into copies of one container, if they all fit and each
+/// copy can be shipped.
+fn single(catalog: &[Container], c: usize, items: &[Item], carrier: &Carrier, options: &PackOptions) -> Result<Option<Shipment>, PackError> {
+    let result = pack_with_options(&catalog[c], items, options)?;
+    if !result.errors.is_empty() {
+        return Ok(None);
+    }
+    let mut cost = 0.0;
+    let mut packings = Vec::with_capacity(result.packings.len());
+    for mut packing in result.packings {
+        match carrier.charge_for(&catalog[c], packing.weight) {
+            Some(charge) => cost += charge,
+            None => return Ok(None),
+        }
+        packing.container_type = c;
+        packings.push(packing);
+    }
+    Ok(Some(Shipment { packings, unplaced: vec![], cost, strategy: result.strategy }))
+}
+
+/// Fills one container at a time with the one that ships the most item
+/// volume per unit of cost, then moves each load into the cheapest
+/// container it still fits into whole.
+fn mixed(catalog: &[Container], items: &[Item], carrier: &Carrier, options: &PackOptions) -> Result<Shipment, PackError> {
+    let mut remaining = items.to_vec();
+    remaining.sort_by(|a, b| b.dimensions.cmp_lwh(&a.dimensions));
+
+    let mut parcels: Vec<Parcel> = Vec::new();
+    while !remaining.is_empty() {
+        let mut best: Option<(f64, Parcel, Vec<usize>)> = None;
+        for (c, container) in catalog.iter().enumerate() {
+            let (packing, packed) = fill_with(container, &remaining, options);
+            if packed.is_empty() {
+                continue;
+            }
+            let charge = match carrier.charge_for(container, packing.weight) {
+                Some(charge) => charge,
+                None => continue,
+            };
+            let volume: f64 = packed.iter().map(|&i| remaining[i].dimensions.volume()).sum();
+            let cost_per_volume = charge / volume;
+            if best.as_ref().is_none_or(|(other, _, _)| cost_per_volume < *other) {
+                let items = packed.iter().map(|&i| remaining[i].clone()).collect();
+                best = Some((cost_per_volume, Parcel { container: c, packing, items }, packed));
+            }
+        }
+        let (_, parcel, packed) = match best {
+            Some(best) => best,
+            None => break,
+        };
+        for &i in packed.iter().rev() {
+            remaining.remove(i);
+        }
+        parcels.push(parcel);
+    }
+
+    let mut cost = 0.0;
+    let mut packings = Vec::with_capacity(parcels.len());
+    for mut parcel in parcels {
+        let weight = parcel.packing.weight;
+        let charge_of = |c: usize| carrier.charge_for(&catalog[c], weight).unwrap_or(f64::INFINITY);
+        let mut charge = charge_of(parcel.container);
+        let mut cheaper: Vec<usize> = (0..catalog.len()).filter(|&c| charge_of(c) < charge).collect();
+        cheaper.sort_by(|&a, &b| charge_of(a).total_cmp(&charge_of(b)));
+        for c in cheaper {
+            let (packing, packed) = fill_with(&catalog[c], &parcel.items, options);
+            if packed.len() == parcel.items.len() {
+                parcel.container = c;
+                parcel.packing = packing;
+                charge = charge_of(c);
+                break;
+            }
+        }
+        cost += charge;
+        parcel.packing.container_type = parcel.container;
+        packings.push(parcel.packing);
+    }
+    Ok(Shipment { packings, unplaced: remaining, cost, strategy: fill_strategy(options.strategy) })
+}
+
+/// Packs `items` into containers from `catalog` so that shipping them with
+/// `carrier` costs the least, or weighs the least if it has no rates.
+///
+/// Both packing everything into copies of each container and filling one
+/// container at a time with the best value one are tried. Every packing
+/// reports the index of its container in
+/// [`Packing::container_type`](crate::Packing::container_type) and its
+/// [`billable_weight`](crate::Packing::billable_weight), the result the
+/// total in [`PackResult::cost`], and [`PackResult::strategy`] is the one
+/// the chosen packings were made with. As with
+/// [`pack_container_types`](crate::pack_container_types) a
+/// [`time_limit_ms`](PackOptions::time_limit_ms) is turned away.
+pub fn pack_for_carrier(
+    catalog: &[Container],
+    items: &[Item],
+    carrier: &Carrier,
+    options: &PackOptions,
+) -> Result<PackResult, PackError> {
+    // items are checked even when the catalog is empty
+    error::check_items(items)?;
+    for container in catalog {
+        error::check_container(container)?;
+    }
+    error::check_options(options)?;
+    error::check_single_pass(options)?;
+    error::check_dimensions(None, &[carrier.dim_divisor; 3])?;
+    for rate in &carrier.rates {
+        error::check_dimensions(None, &[rate.max_weight; 3])?;
+        error::check_cost(rate.cost)?;
+    }
+
+    let mut best = mixed(catalog, items, carrier, options)?;
+    for c in 0..catalog.len() {
+        if let Some(shipment) = single(catalog, c, items, carrier, options)? {
+            if shipment.is_better_than(&best) {
+                best = shipment;
+            }
+        }
+    }
+
+    let heaviest_rate = carrier.rates.iter().map(|rate| rate.max_weight).reduce(f64::max);
+    let (unpacked, errors): (Vec<Unpacked>, Vec<String>) = best
+        .unplaced
+        .iter()
+        .map(|item| {
+            let fits_some_container =
+                catalog.iter().any(|container| !fill_with(container, std::slice::from_ref(item), options).1.is_empty());
+            if fits_some_container {
+                let unpacked = Unpacked { item: item.clone(), reason: Reason::TooHeavy, limit: heaviest_rate };
+                (unpacked, format!("Item: {} cannot be placed: too heavy for any rate", item))
+            } else {
+                (Unpacked::classify_biggest(catalog.iter(), item, options), format!("Item: {} cannot be placed in any container", item))
+            }
+        })
+        .unzip();
+    let packings = best
+        .packings
+        .into_iter()
+        .map(|mut packing| {
+            packing.billable_weight = Some(carrier.billable_weight(&catalog[packing.container_type], packing.weight));
+            packing
+        })
+        .collect();
+    Ok(PackResult {
+        packings,
+        errors,
+        unpacked,
+        strategy: best.strategy,
+        lower_bounds: None,
+        proven_optimal: false,
+        cost: Some(best.cost),
+    })
+}
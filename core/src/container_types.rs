@@ -0,0 +1,180 @@
+use crate::error::{self, PackError};
+use crate::extreme_points::ExtremePoints;
+use crate::frame::Frame;
+use crate::item::{Container, Item};
+use crate::layout::fill;
+use crate::maximal_spaces::MaximalSpaces;
+use crate::options::{PackOptions, Strategy};
+use crate::packing::{PackResult, Packing};
+use crate::place::GuillotineSpaces;
+use crate::unpacked::{Reason, Unpacked};
+
+/// A kind of container on offer when packing with
+/// [`pack_container_types`].
+#[derive(Clone, Debug)]
+pub struct ContainerType {
+    pub container: Container,
+    pub cost: f64,
+    /// How many are available, any number if `None`.
+    pub stock: Option<usize>,
+}
+
+impl ContainerType {
+    pub fn new(container: Container, cost: f64) -> ContainerType {
+        ContainerType { container, cost, stock: None }
+    }
+
+    /// Limits how many of the type may be used.
+    pub fn with_stock(mut self, stock: usize) -> ContainerType {
+        self.stock = Some(stock);
+        self
+    }
+}
+
+/// The strategy [`fill_with`] fills containers with when asked for
+/// `strategy`. Strategies that work across containers fill with free spaces.
+pub(crate) fn fill_strategy(strategy: Strategy) -> Strategy {
+    match strategy {
+        Strategy::Spaces | Strategy::ExtremePoints(_) | Strategy::MaximalSpaces => strategy,
+        Strategy::Layers | Strategy::Exact | Strategy::GreedyBox => Strategy::Spaces,
+    }
+}
+
+/// Fills one `container` with `items` using the layout of the
+/// [`fill_strategy`] of `options`.
+pub(crate) fn fill_with(container: &Container, items: &[Item], options: &PackOptions) -> (Packing, Vec<usize>) {
+    let frame = Frame::new(container, options);
+    let (container, items, packing_options) = (&frame.container(container), frame.items(items), frame.options(options));
+    let (packing, packed) = match fill_strategy(options.strategy) {
+        Strategy::ExtremePoints(_) => fill::<ExtremePoints>(container, &items, &packing_options),
+        Strategy::MaximalSpaces => fill::<MaximalSpaces>(container, &items, &packing_options),
+        _ => fill::<GuillotineSpaces>(container, &items, &packing_options),
+    };
+    (frame.packing(packing, options), packed)
+}
+
+/// A packing of some of the items into one container of a type.
+struct Filled {
+    container_type: usize,
+    packing: Packing,
+    items: Vec<Item>,
+}
+
+/// Packs `items` into containers of the given `types`, looking for the
+/// lowest total cost.
+///
+/// Containers are filled one at a time with the type that packs the most
+/// item volume per unit of cost, as long as it is in stock. Afterwards each
+/// container is swapped for the cheapest type that still holds everything
+/// in it. Every packing reports the index of its type in
+/// [`Packing::container_type`].
+///
+/// [`Strategy::Layers`], [`Strategy::Exact`] and [`Strategy::GreedyBox`]
+/// fill each container with free spaces instead, as
+/// [`PackResult::strategy`] reports, and a
+/// [`time_limit_ms`](PackOptions::time_limit_ms) is turned away.
+pub fn pack_container_types(types: &[ContainerType], items: &[Item], options: &PackOptions) -> Result<PackResult, PackError> {
+    // items are checked even when there is no container type to put them in
+    error::check_items(items)?;
+    for container_type in types {
+        error::check_container(&container_type.container)?;
+        error::check_cost(container_type.cost)?;
+    }
+    error::check_options(options)?;
+    error::check_single_pass(options)?;
+    let mut remaining = items.to_vec();
+    remaining.sort_by(|a, b| b.dimensions.cmp_lwh(&a.dimensions));
+    let mut stock: Vec<Option<usize>> = types.iter().map(|container_type| container_type.stock).collect();
+
+    let mut filled: Vec<Filled> = Vec::new();
+    while !remaining.is_empty() {
+        let mut best: Option<(f64, Filled, Vec<usize>)> = None;
+        for (t, container_type) in types.iter().enumerate() {
+            if stock[t] == Some(0) {
+                continue;
+            }
+            let (packing, packed) = fill_with(&container_type.container, &remaining, options);
+            if packed.is_empty() {
+                continue;
+            }
+            let volume: f64 = packed.iter().map(|&i| remaining[i].dimensions.volume()).sum();
+            let cost_per_volume = container_type.cost / volume;
+            if best.as_ref().is_none_or(|(other, _, _)| cost_per_volume < *other) {
+                let items = packed.iter().map(|&i| remaining[i].clone()).collect();
+                best = Some((cost_per_volume, Filled { container_type: t, packing, items }, packed));
+            }
+        }
+        let (_, chosen, packed) = match best {
+            Some(best) => best,
+            None => break,
+        };
+        if let Some(left) = stock[chosen.container_type].as_mut() {
+            *left -= 1;
+        }
+        for &i in packed.iter().rev() {
+            remaining.remove(i);
+        }
+        filled.push(chosen);
+    }
+
+    // a cheaper type may hold what ended up in a container on its own
+    for chosen in &mut filled {
+        let mut cheaper: Vec<usize> = (0..types.len())
+            .filter(|&t| types[t].cost < types[chosen.container_type].cost && stock[t] != Some(0))
+            .collect();
+        cheaper.sort_by(|&a, &b| types[a].cost.total_cmp(&types[b].cost));
+        for t in cheaper {
+            let (packing, packed) = fill_with(&types[t].container, &chosen.items, options);
+            if packed.len() == chosen.items.len() {
+                if let Some(left) = stock[chosen.container_type].as_mut() {
+                    *left += 1;
+                }
+                if let Some(left) = stock[t].as_mut() {
+                    *left -= 1;
+                }
+                chosen.container_type = t;
+                chosen.packing = packing;
+                break;
+            }
+        }
+    }
+
+    let unpacked: Vec<Unpacked> = remaining
+        .iter()
+        .map(|item| {
+            let fits_some_type = types.iter().any(|container_type| {
+                !fill_with(&container_type.container, std::slice::from_ref(item), options).1.is_empty()
+            });
+            if fits_some_type {
+                Unpacked { item: item.clone(), reason: Reason::ExceedsStock, limit: None }
+            } else {
+                Unpacked::classify_biggest(types.iter().map(|container_type| &container_type.container), item, options)
+            }
+        })
+        .collect();
+    let errors = unpacked
+        .iter()
+        .map(|unpacked| match unpacked.reason {
+            Reason::ExceedsStock => unpacked.to_string(),
+            _ => format!("Item: {} cannot be placed in any container", unpacked.item),
+        })
+        .collect();
+    let cost = filled.iter().map(|chosen| types[chosen.container_type].cost).sum();
+    let packings = filled
+        .into_iter()
+        .map(|chosen| {
+            let mut packing = chosen.packing;
+            packing.container_type = chosen.container_type;
+            packing
+        })
+        .collect();
+    Ok(PackResult {
+        packings,
+        errors,
+        unpacked,
+        strategy: fill_strategy(options.strategy),
+        lower_bounds: None,
+        proven_optimal: false,
+        cost: Some(cost),
+    })
+}
@@ -0,0 +1,172 @@
+use crate::bounds::ceil_div;
+use crate::error::{self, PackError};
+use crate::geometry::{Coordinates, Dimensions};
+use crate::greedy::item_greedy_box;
+use crate::item::{Container, Item, WeightLimit};
+use crate::options::PackOptions;
+use crate::pack_with_options;
+use crate::packing::Packing;
+use crate::smallest::find_smallest_containers;
+
+/// How many of the smallest containers to try sizing up.
+const CANDIDATES: usize = 10;
+
+/// What a box-on-demand machine can make, and what its boxes cost.
+///
+/// All sizes are outer sizes, sides in any order.
+#[derive(Clone, Debug)]
+pub struct BoxSpec {
+    pub max_dimensions: Coordinates,
+    pub min_dimensions: Option<Coordinates>,
+    /// Outer sides are cut to multiples of this, any size if `None`.
+    pub increment: Option<f64>,
+    /// Taken off both sides of every outer side to get the inner size.
+    pub wall_thickness: f64,
+    pub weight_limit: WeightLimit,
+    /// Cost per unit of board area, the outer surface of the box.
+    pub board_cost: f64,
+    /// Cost per unit of inner volume not taken up by items.
+    pub void_cost: f64,
+}
+
+impl BoxSpec {
+    /// Boxes up to `max_dimensions` made of board costing 1 per unit area.
+    pub fn new(max_dimensions: Coordinates) -> BoxSpec {
+        BoxSpec {
+            max_dimensions,
+            min_dimensions: None,
+            increment: None,
+            wall_thickness: 0.0,
+            weight_limit: WeightLimit::Unlimited,
+            board_cost: 1.0,
+            void_cost: 0.0,
+        }
+    }
+
+    pub fn with_min_dimensions(mut self, min_dimensions: Coordinates) -> BoxSpec {
+        self.min_dimensions = Some(min_dimensions);
+        self
+    }
+
+    pub fn with_increment(mut self, increment: f64) -> BoxSpec {
+        self.increment = Some(increment);
+        self
+    }
+
+    pub fn with_wall_thickness(mut self, wall_thickness: f64) -> BoxSpec {
+        self.wall_thickness = wall_thickness;
+        self
+    }
+
+    pub fn with_weight_limit(mut self, weight_limit: WeightLimit) -> BoxSpec {
+        self.weight_limit = weight_limit;
+        self
+    }
+
+    pub fn with_costs(mut self, board_cost: f64, void_cost: f64) -> BoxSpec {
+        self.board_cost = board_cost;
+        self.void_cost = void_cost;
+        self
+    }
+
+    /// The outer sides the machine makes for a box holding `inner`, sorted
+    /// ascending, `None` if that is beyond its maximum.
+    fn outer(&self, inner: &Coordinates) -> Option<Coordinates> {
+        let mut outer = sorted(inner.map(|side| side + 2.0 * self.wall_thickness));
+        if let Some(increment) = self.increment {
+            outer = outer.map(|side| ceil_div(side, increment) as f64 * increment);
+        }
+        if let Some(min_dimensions) = self.min_dimensions {
+            let min_dimensions = sorted(min_dimensions);
+            outer = sorted([outer[0].max(min_dimensions[0]), outer[1].max(min_dimensions[1]), outer[2].max(min_dimensions[2])]);
+        }
+        let max_dimensions = sorted(self.max_dimensions);
+        if (0..3).all(|axis| outer[axis] <= max_dimensions[axis] + 1e-9) {
+            Some(outer)
+        } else {
+            None
+        }
+    }
+
+    fn cost(&self, outer: &Coordinates, inner: &Dimensions, items: &[Item]) -> f64 {
+        let area = 2.0 * (outer[0] * outer[1] + outer[1] * outer[2] + outer[0] * outer[2]);
+        let void = inner.volume() - items.iter().map(|item| item.dimensions.volume()).sum::<f64>();
+        self.board_cost * area + self.void_cost * void.max(0.0)
+    }
+}
+
+fn sorted(mut a: Coordinates) -> Coordinates {
+    a.sort_by(|x, y| x.total_cmp(y));
+    a
+}
+
+/// A box made to measure by [`design_box`].
+#[derive(Clone, Debug)]
+pub struct DesignedBox {
+    pub inner: Dimensions,
+    pub outer: Dimensions,
+    pub cost: f64,
+    /// All the items packed into the inner size.
+    pub packing: Packing,
+}
+
+/// Designs the cheapest box `spec` allows that holds all of `items` in a
+/// single packing, `None` if no such box can be made.
+///
+/// The smallest containers found by [`find_smallest_containers`] and the
+/// [greedy box](item_greedy_box) are grown by the walls, rounded up to the
+/// increment and minimum sizes and packed again, since the extra room may
+/// let the items settle differently.
+pub fn design_box(items: &[Item], spec: &BoxSpec, options: &PackOptions) -> Result<Option<DesignedBox>, PackError> {
+    error::check_dimensions(None, &spec.max_dimensions)?;
+    if let Some(min_dimensions) = spec.min_dimensions {
+        error::check_dimensions(None, &min_dimensions)?;
+    }
+    if let Some(increment) = spec.increment {
+        error::check_dimensions(None, &[increment; 3])?;
+    }
+    if !spec.wall_thickness.is_finite() || spec.wall_thickness < 0.0 {
+        return Err(PackError::InvalidDimension { item: None, value: spec.wall_thickness });
+    }
+    error::check_cost(spec.board_cost)?;
+    error::check_cost(spec.void_cost)?;
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let limit = sorted(spec.max_dimensions).map(|side| side - 2.0 * spec.wall_thickness);
+    if limit.iter().any(|&side| side <= 0.0) {
+        return Ok(None);
+    }
+    let mut candidates: Vec<Coordinates> = find_smallest_containers(items, CANDIDATES, Some(limit))?
+        .into_iter()
+        .map(|dimensions| dimensions.dimensions)
+        .collect();
+    candidates.push(item_greedy_box(items).dimensions);
+
+    let mut outers: Vec<Coordinates> = Vec::new();
+    let mut best: Option<DesignedBox> = None;
+    for candidate in candidates {
+        let outer = match spec.outer(&candidate) {
+            Some(outer) if !outers.contains(&outer) => outer,
+            _ => continue,
+        };
+        outers.push(outer);
+        let inner = outer.map(|side| side - 2.0 * spec.wall_thickness);
+        let container = Container::new(inner, spec.weight_limit).with_outer_dimensions(outer);
+        let mut result = pack_with_options(&container, items, options)?;
+        if result.packings.len() != 1 || !result.errors.is_empty() {
+            continue;
+        }
+        let cost = spec.cost(&outer, &container.dimensions, items);
+        if best.as_ref().is_none_or(|best| cost < best.cost) {
+            best = Some(DesignedBox {
+                inner: container.dimensions,
+                outer: container.outer_dimensions,
+                cost,
+                packing: result.packings.remove(0),
+            });
+        }
+    }
+    Ok(best)
+}
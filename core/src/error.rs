@@ -0,0 +1,155 @@
+use std::error::Error;
+use std::fmt;
+
+/// Invalid input handed to [`pack`](crate::pack).
+///
+/// `item` is the index of the offending item in the slice given to `pack`,
+/// or `None` when the container itself is at fault.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PackError {
+    /// A required field was not given.
+    MissingField { item: Option<usize>, field: &'static str },
+    /// A field was given with a value of the wrong type.
+    WrongType { item: Option<usize>, field: &'static str, expected: &'static str },
+    /// A list field was given with the wrong number of elements.
+    WrongArity { item: Option<usize>, field: &'static str, expected: usize, actual: usize },
+    /// A dimension is negative, zero, infinite or not a number.
+    InvalidDimension { item: Option<usize>, value: f64 },
+    /// A weight is negative, infinite or not a number.
+    InvalidWeight { item: Option<usize>, value: f64 },
+    /// An allowed rotation is not a permutation of the axes.
+    InvalidRotation { item: Option<usize>, rotation: [usize; 3] },
+    /// A container cost is negative, infinite or not a number.
+    InvalidCost { value: f64 },
+}
+
+impl PackError {
+    /// Index of the offending item, `None` if the container is at fault.
+    pub fn item_index(&self) -> Option<usize> {
+        match *self {
+            PackError::MissingField { item, .. }
+            | PackError::WrongType { item, .. }
+            | PackError::WrongArity { item, .. }
+            | PackError::InvalidDimension { item, .. }
+            | PackError::InvalidWeight { item, .. }
+            | PackError::InvalidRotation { item, .. } => item,
+            PackError::InvalidCost { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.item_index() {
+            Some(i) => write!(f, "item {}: ", i)?,
+            None => write!(f, "container: ")?,
+        }
+        match self {
+            PackError::MissingField { field, .. } => write!(f, "missing :{}", field),
+            PackError::WrongType { field, expected, .. } => write!(f, ":{} must be {}", field, expected),
+            PackError::WrongArity { field, expected, actual, .. } => {
+                write!(f, ":{} must have {} elements, got {}", field, expected, actual)
+            }
+            PackError::InvalidDimension { value, .. } => write!(f, "dimension {} must be a positive number", value),
+            PackError::InvalidWeight { value, .. } => write!(f, "weight {} must be a non-negative number", value),
+            PackError::InvalidRotation { rotation, .. } => {
+                write!(f, "rotation {:?} must be a permutation of [0, 1, 2]", rotation)
+            }
+            PackError::InvalidCost { value } => write!(f, "cost {} must be a non-negative number", value),
+        }
+    }
+}
+
+impl Error for PackError {}
+
+/// Checks that every dimension is a positive number, every weight and load
+/// limit is a non-negative number and every allowed rotation turns the item
+/// onto all three axes.
+pub(crate) fn check_input(container: &crate::Container, items: &[crate::Item]) -> Result<(), PackError> {
+    check_container(container)?;
+    check_items(items)
+}
+
+pub(crate) fn check_container(container: &crate::Container) -> Result<(), PackError> {
+    check_dimensions(None, &container.dimensions.dimensions)?;
+    check_dimensions(None, &container.outer_dimensions.dimensions)?;
+    check_weight(None, container.weight_limit.bound())?;
+    check_weight(None, Some(container.tare_weight))
+}
+
+pub(crate) fn check_items(items: &[crate::Item]) -> Result<(), PackError> {
+    for (i, item) in items.iter().enumerate() {
+        check_item(Some(i), item)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn check_item(index: Option<usize>, item: &crate::Item) -> Result<(), PackError> {
+    check_dimensions(index, &item.dimensions.dimensions)?;
+    check_weight(index, item.weight)?;
+    check_weight(index, item.max_load)?;
+    for rotation in item.allowed_rotations.iter().flatten() {
+        check_rotation(index, rotation)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn check_dimensions(item: Option<usize>, dimensions: &[f64; 3]) -> Result<(), PackError> {
+    for &value in dimensions {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(PackError::InvalidDimension { item, value });
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn check_cost(value: f64) -> Result<(), PackError> {
+    if !value.is_finite() || value < 0.0 {
+        return Err(PackError::InvalidCost { value });
+    }
+    Ok(())
+}
+
+fn check_rotation(item: Option<usize>, rotation: &[usize; 3]) -> Result<(), PackError> {
+    let mut sorted = *rotation;
+    sorted.sort_unstable();
+    if sorted != [0, 1, 2] {
+        return Err(PackError::InvalidRotation { item, rotation: *rotation });
+    }
+    Ok(())
+}
+
+fn check_weight(item: Option<usize>, weight: Option<f64>) -> Result<(), PackError> {
+    match weight {
+        Some(value) if !value.is_finite() || value < 0.0 => Err(PackError::InvalidWeight { item, value }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `options` ask for nothing packing into several container
+/// types can't do: searching for better orders takes a single container.
+pub(crate) fn check_single_pass(options: &crate::PackOptions) -> Result<(), PackError> {
+    if options.time_limit_ms.is_some() {
+        let expected = "nil when packing into several container types";
+        return Err(PackError::WrongType { item: None, field: "time_limit_ms", expected });
+    }
+    Ok(())
+}
+
+/// Checks that the support share lies between 0 and 1, the centre of
+/// gravity envelope is made of non-negative numbers and legacy positions
+/// are only asked of strategies placing everything into free spaces.
+pub(crate) fn check_options(options: &crate::PackOptions) -> Result<(), PackError> {
+    let spaced = matches!(options.strategy, crate::Strategy::Spaces | crate::Strategy::Exact);
+    if options.legacy_positions && (!spaced || options.given_axes) {
+        let expected = "false unless packing with :spaces or :exact along sorted sides";
+        return Err(PackError::WrongType { item: None, field: "legacy_positions", expected });
+    }
+    if options.min_support.is_some_and(|ratio| !(0.0..=1.0).contains(&ratio)) {
+        return Err(PackError::WrongType { item: None, field: "min_support", expected: "a number between 0 and 1" });
+    }
+    if options.cog_envelope.is_some_and(|envelope| envelope.iter().any(|&side| !side.is_finite() || side < 0.0)) {
+        return Err(PackError::WrongType { item: None, field: "cog_envelope", expected: "non-negative numbers" });
+    }
+    Ok(())
+}
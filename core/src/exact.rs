@@ -0,0 +1,153 @@
+use crate::bounds::{ceil_div, lower_bounds};
+use crate::item::{Container, Item};
+use crate::layout::{finish, first_fit, Layout};
+use crate::options::PackOptions;
+use crate::packing::{Packing, Rotation};
+use crate::place::{allowed_rotations, rotate, GuillotineSpaces};
+use crate::unpacked::Unpacked;
+use std::time::{Duration, Instant};
+
+/// Nodes searched when no limit is given.
+const DEFAULT_NODE_LIMIT: u64 = 100_000;
+
+type Open = Vec<(Packing, GuillotineSpaces)>;
+
+struct Search<'a> {
+    container: &'a Container,
+    items: &'a [Item],
+    options: &'a PackOptions,
+    started: Instant,
+    time_limit: Option<Duration>,
+    node_limit: u64,
+    nodes: u64,
+    lower_bound: usize,
+    best: Vec<Packing>,
+}
+
+/// The rotations of `item` giving different extents.
+fn distinct_rotations(item: &Item) -> Vec<Rotation> {
+    let mut distinct: Vec<Rotation> = Vec::with_capacity(6);
+    for rotation in allowed_rotations(item).iter() {
+        let extents = rotate(&item.dimensions, rotation);
+        if !distinct.iter().any(|other| rotate(&item.dimensions, other) == extents) {
+            distinct.push(*rotation);
+        }
+    }
+    distinct
+}
+
+fn same_item(a: &Item, b: &Item) -> bool {
+    a.dimensions.dimensions == b.dimensions.dimensions
+        && a.weight == b.weight
+        && a.allowed_rotations == b.allowed_rotations
+        && a.max_load == b.max_load
+        && a.stackable == b.stackable
+}
+
+impl Search<'_> {
+    fn out_of_budget(&self) -> bool {
+        self.nodes >= self.node_limit || self.time_limit.is_some_and(|limit| self.started.elapsed() >= limit)
+    }
+
+    /// Containers any completion of `open` with the `remaining` items needs
+    /// at least, counting the room and weight left in those already open.
+    fn bound(&self, open: &Open, remaining: &[usize]) -> usize {
+        let volume: f64 = remaining.iter().map(|&i| self.items[i].dimensions.volume()).sum();
+        let room: f64 = open.iter().map(|(packing, _)| packing.free_volume()).sum();
+        let mut bound = open.len() + ceil_div(volume - room, self.container.dimensions.volume());
+        if let Some(limit) = self.container.payload_limit() {
+            let weight: f64 = remaining.iter().map(|&i| self.items[i].weight.unwrap_or(0.0)).sum();
+            let spare: f64 = open.iter().map(|(packing, _)| limit - packing.weight).sum();
+            bound = bound.max(open.len() + ceil_div(weight - spare, limit));
+        }
+        bound
+    }
+
+    /// Depth first over which item goes next, into which container and
+    /// turned which way. Returns whether the budget ran out.
+    fn search(&mut self, open: &Open, remaining: &[usize]) -> bool {
+        self.nodes += 1;
+        if self.out_of_budget() {
+            return true;
+        }
+        if remaining.is_empty() {
+            if open.len() < self.best.len() {
+                self.best = open.iter().map(|(packing, layout)| finish(packing.clone(), layout, self.container, self.options)).collect();
+            }
+            return false;
+        }
+        if self.bound(open, remaining) >= self.best.len() {
+            return false;
+        }
+
+        for (n, &i) in remaining.iter().enumerate() {
+            let item = &self.items[i];
+            // identical items lead to the same packings
+            if remaining[..n].iter().any(|&j| same_item(&self.items[j], item)) {
+                continue;
+            }
+            let rest: Vec<usize> = remaining.iter().copied().filter(|&j| j != i).collect();
+            let weight = item.weight.unwrap_or(0.0);
+            // a new container only pays off if it still beats the best
+            let containers = if open.len() + 1 < self.best.len() { open.len() + 1 } else { open.len() };
+            for c in 0..containers {
+                if c < open.len() && !self.container.allows(open[c].0.weight + weight) {
+                    continue;
+                }
+                for rotation in distinct_rotations(item) {
+                    let mut next = open.clone();
+                    if c == open.len() {
+                        next.push((Packing::new(self.container), GuillotineSpaces::new(self.container, self.options)));
+                    }
+                    let (packing, layout) = &mut next[c];
+                    let placement = match layout.place_exactly(item, &rotation, &packing.placements) {
+                        Some(placement) => placement,
+                        None => continue,
+                    };
+                    packing.placements.push(placement);
+                    packing.weight += weight;
+                    if self.search(&next, &rest) {
+                        return true;
+                    }
+                    if self.best.len() == self.lower_bound {
+                        return false;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Searches the packing orders and rotations of `items` for fewer containers
+/// than the free space heuristic needs, stopping at the lower bound or when
+/// the node or time limit of `options` runs out.
+///
+/// Items [`first_fit`] leaves out are reported as errors just as it does,
+/// and left out of the search.
+pub(crate) fn pack_exact(container: &Container, items: &[Item], options: &PackOptions) -> (Vec<Packing>, Vec<Unpacked>) {
+    let (packings, errors) = first_fit::<GuillotineSpaces>(container, items, options);
+    let mut items = items.to_vec();
+    for unpacked in &errors {
+        let left_out = |item: &Item| item.id == unpacked.item.id && item.instance == unpacked.item.instance && same_item(item, &unpacked.item);
+        if let Some(i) = items.iter().position(left_out) {
+            items.remove(i);
+        }
+    }
+    let mut search = Search {
+        container,
+        items: &items,
+        options,
+        started: Instant::now(),
+        time_limit: options.time_limit_ms.map(Duration::from_millis),
+        node_limit: options.node_limit.unwrap_or(DEFAULT_NODE_LIMIT),
+        nodes: 0,
+        lower_bound: lower_bounds(container, &items).best(),
+        best: packings,
+    };
+    if search.best.len() > search.lower_bound {
+        let remaining: Vec<usize> = (0..items.len()).collect();
+        search.search(&Vec::new(), &remaining);
+    }
+    (search.best, errors)
+}
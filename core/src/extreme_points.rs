@@ -0,0 +1,163 @@
+use crate::geometry::{cmp_coordinates, Coordinates, Dimensions};
+use crate::item::{Container, Item};
+use crate::layout::Layout;
+use crate::options::{Merit, PackOptions, Strategy};
+use crate::packing::{Placement, Space};
+use crate::place::{allowed_rotations, rotate};
+use crate::support::Stability;
+
+/// The free room of a container as the extreme points of Crainic, Perboli
+/// and Tadei: corners of placed items projected back onto the container
+/// walls or the items in the way.
+pub(crate) struct ExtremePoints {
+    bounds: Coordinates,
+    points: Vec<Coordinates>,
+    merit: Merit,
+    stability: Stability,
+}
+
+/// Whether `a` lies within the half-open extent of `placement` along `axis`.
+fn within(placement: &Placement, axis: usize, a: f64) -> bool {
+    placement.position[axis] <= a && a < placement.position[axis] + placement.dimensions[axis]
+}
+
+fn overlaps(placement: &Placement, position: &Coordinates, extents: &Coordinates) -> bool {
+    (0..3).all(|axis| {
+        position[axis] < placement.position[axis] + placement.dimensions[axis]
+            && placement.position[axis] < position[axis] + extents[axis]
+    })
+}
+
+/// Room along each axis from `point` up to the container wall or the first
+/// placement in the way.
+fn residual_space(point: &Coordinates, bounds: &Coordinates, placements: &[Placement]) -> Coordinates {
+    let mut residual = [bounds[0] - point[0], bounds[1] - point[1], bounds[2] - point[2]];
+    for placement in placements {
+        for axis in 0..3 {
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            if placement.position[axis] >= point[axis] && within(placement, b, point[b]) && within(placement, c, point[c]) {
+                residual[axis] = residual[axis].min(placement.position[axis] - point[axis]);
+            }
+        }
+    }
+    residual
+}
+
+/// [`residual_space`] cut back until it overlaps none of `placements`,
+/// keeping the most room each cut; no room at all if `point` lies inside
+/// one of them.
+fn free_space(point: &Coordinates, bounds: &Coordinates, placements: &[Placement]) -> Coordinates {
+    let mut extents = residual_space(point, bounds, placements);
+    while let Some(placement) = placements.iter().find(|placement| overlaps(placement, point, &extents)) {
+        extents = (0..3)
+            .filter(|&axis| placement.position[axis] > point[axis])
+            .map(|axis| {
+                let mut cut = extents;
+                cut[axis] = placement.position[axis] - point[axis];
+                cut
+            })
+            .max_by(|a, b| (a[0] * a[1] * a[2]).total_cmp(&(b[0] * b[1] * b[2])))
+            .unwrap_or([0.0; 3]);
+    }
+    extents
+}
+
+/// Moves `point` towards the origin along `axis` until it rests on a
+/// placement or the container wall.
+fn project(point: &Coordinates, axis: usize, placements: &[Placement]) -> Coordinates {
+    let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+    let mut projected = *point;
+    projected[axis] = 0.0;
+    for placement in placements {
+        let end = placement.position[axis] + placement.dimensions[axis];
+        if end <= point[axis] && end > projected[axis] && within(placement, b, point[b]) && within(placement, c, point[c]) {
+            projected[axis] = end;
+        }
+    }
+    projected
+}
+
+impl ExtremePoints {
+    fn fits(&self, position: &Coordinates, extents: &Coordinates, placements: &[Placement]) -> bool {
+        (0..3).all(|axis| position[axis] + extents[axis] <= self.bounds[axis])
+            && !placements.iter().any(|placement| overlaps(placement, position, extents))
+    }
+
+    fn merit(&self, residual: &Coordinates, extents: &Coordinates) -> f64 {
+        match self.merit {
+            Merit::ResidualSpace => (residual[0] - extents[0]) + (residual[1] - extents[1]) + (residual[2] - extents[2]),
+            Merit::FreeVolume => residual[0] * residual[1] * residual[2] - extents[0] * extents[1] * extents[2],
+        }
+    }
+
+    /// Adds the projections of the new placement's corners and drops the
+    /// points it now covers.
+    fn update(&mut self, placed: &Placement, placements: &[Placement]) {
+        let mut all = placements.to_vec();
+        all.push(placed.clone());
+        for axis in 0..3 {
+            let mut corner = placed.position;
+            corner[axis] += placed.dimensions[axis];
+            if corner[axis] >= self.bounds[axis] {
+                continue;
+            }
+            for towards in (0..3).filter(|&a| a != axis) {
+                self.points.push(project(&corner, towards, &all));
+            }
+        }
+        self.points.retain(|point| !(0..3).all(|axis| within(placed, axis, point[axis])));
+        // lowest first, then closest to the back and to the left
+        self.points.sort_by(|a, b| cmp_coordinates(&[a[2], a[1], a[0]], &[b[2], b[1], b[0]]));
+        self.points.dedup();
+    }
+}
+
+impl Layout for ExtremePoints {
+    fn new(container: &Container, options: &PackOptions) -> Self {
+        let merit = match options.strategy {
+            Strategy::ExtremePoints(merit) => merit,
+            _ => Merit::default(),
+        };
+        ExtremePoints {
+            bounds: container.dimensions.dimensions,
+            points: vec![[0.0, 0.0, 0.0]],
+            merit,
+            stability: Stability::new(container, options),
+        }
+    }
+
+    fn place(&mut self, item: &Item, placements: &[Placement]) -> Option<Placement> {
+        let mut best: Option<(f64, Placement)> = None;
+        for point in &self.points {
+            let residual = residual_space(point, &self.bounds, placements);
+            for rotation in allowed_rotations(item).iter() {
+                let extents = rotate(&item.dimensions, rotation);
+                if !self.fits(point, &extents, placements) {
+                    continue;
+                }
+                let candidate = Placement::new(item, *rotation, *point);
+                if !self.stability.allows(placements, &candidate) {
+                    continue;
+                }
+                let merit = self.merit(&residual, &extents);
+                if best.as_ref().is_none_or(|(best_merit, _)| merit < *best_merit) {
+                    best = Some((merit, candidate));
+                }
+            }
+        }
+        let (_, placement) = best?;
+        self.update(&placement, placements);
+        Some(placement)
+    }
+
+    fn spaces(&self, placements: &[Placement]) -> Vec<Space> {
+        self.points
+            .iter()
+            .map(|point| Space {
+                dimensions: Dimensions::from_array(&free_space(point, &self.bounds, placements)),
+                position: *point,
+            })
+            .filter(|space| space.volume() > 0.0)
+            .collect()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::geometry::Dimensions;
+use crate::item::{Container, Item};
+use crate::options::PackOptions;
+use crate::packing::{Packing, Rotation};
+use crate::unpacked::Unpacked;
+
+/// How a container's axes map onto the axes packing works along, and
+/// which of them results are reported along.
+///
+/// By default packing works along the container's sides sorted longest
+/// first, the shortest being the height, and reports along them too. With
+/// [`given_axes`](PackOptions::given_axes) it works along the longer side of
+/// the floor, its shorter side and the height, the container's last
+/// dimension, while rotations and the balance envelope come in, and
+/// packings go out, along the container's dimensions as given.
+///
+/// Packing itself reads the container's dimensions as given, so it is
+/// handed the [`container`](Self::container) along its packing axes.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Frame {
+    /// For each packing axis, the container axis it runs along.
+    axes: [usize; 3],
+    /// Whether results are reported along the container's dimensions as
+    /// given rather than along the packing axes.
+    given: bool,
+}
+
+impl Frame {
+    pub(crate) fn new(container: &Container, options: &PackOptions) -> Frame {
+        let sides = container.dimensions.dimensions;
+        let mut axes = [0, 1, 2];
+        // stable, so equal sides keep the order given
+        let sorted = if options.given_axes { &mut axes[..2] } else { &mut axes[..] };
+        sorted.sort_by(|&a, &b| sides[b].total_cmp(&sides[a]));
+        Frame { axes, given: options.given_axes }
+    }
+
+    /// `dimensions` of the container along the packing axes.
+    fn along_packing_axes(&self, dimensions: &Dimensions) -> Dimensions {
+        let sides = dimensions.dimensions;
+        Dimensions::from_array(&[sides[self.axes[0]], sides[self.axes[1]], sides[self.axes[2]]])
+    }
+
+    /// `values` along the container's own axes rearranged along the packing
+    /// axes.
+    fn inward<T: Copy>(&self, values: &[T; 3]) -> [T; 3] {
+        if !self.given {
+            return *values;
+        }
+        [values[self.axes[0]], values[self.axes[1]], values[self.axes[2]]]
+    }
+
+    /// `values` along the packing axes rearranged along the container's own
+    /// axes.
+    fn outward<T: Copy>(&self, values: &[T; 3]) -> [T; 3] {
+        let mut given = *values;
+        if !self.given {
+            return given;
+        }
+        for (axis, &value) in values.iter().enumerate() {
+            given[self.axes[axis]] = value;
+        }
+        given
+    }
+
+    fn map_rotations(item: &Item, map: impl Fn(&Rotation) -> Rotation) -> Item {
+        Item { allowed_rotations: item.allowed_rotations.as_ref().map(|rotations| rotations.iter().map(map).collect()), ..item.clone() }
+    }
+
+    /// `container` with its dimensions given along the packing axes.
+    pub(crate) fn container(&self, container: &Container) -> Container {
+        Container {
+            dimensions: self.along_packing_axes(&container.dimensions),
+            outer_dimensions: self.along_packing_axes(&container.outer_dimensions),
+            ..container.clone()
+        }
+    }
+
+    /// `container` with its dimensions along the axes results are reported
+    /// along.
+    pub(crate) fn reported(&self, container: &Container) -> Container {
+        if self.given {
+            container.clone()
+        } else {
+            self.container(container)
+        }
+    }
+
+    /// `item` with its allowed rotations along the packing axes.
+    pub(crate) fn item(&self, item: &Item) -> Item {
+        Frame::map_rotations(item, |rotation| self.inward(rotation))
+    }
+
+    /// [`item`](Self::item) for each of `items`.
+    pub(crate) fn items(&self, items: &[Item]) -> Vec<Item> {
+        items.iter().map(|item| self.item(item)).collect()
+    }
+
+    /// `options` with the balance envelope along the packing axes.
+    pub(crate) fn options(&self, options: &PackOptions) -> PackOptions {
+        PackOptions { cog_envelope: options.cog_envelope.map(|envelope| self.inward(&envelope)), ..options.clone() }
+    }
+
+    fn extents(&self, dimensions: &Dimensions) -> Dimensions {
+        Dimensions::from_array(&self.outward(&dimensions.dimensions))
+    }
+
+    /// `packing`, made along the packing axes, as reported along the
+    /// container's own axes, with the positions `options` ask for.
+    pub(crate) fn packing(&self, mut packing: Packing, options: &PackOptions) -> Packing {
+        packing.outer_dimensions = self.extents(&packing.outer_dimensions);
+        for placement in &mut packing.placements {
+            placement.dimensions = self.extents(&placement.dimensions);
+            placement.position = self.outward(&placement.position);
+            placement.rotation = self.outward(&placement.rotation);
+            if options.legacy_positions {
+                // earlier releases reported the space along the sorted sides,
+                // which is how packing saw it and, without given axes, how
+                // the rest is reported too
+                placement.position = placement.space.unwrap_or(placement.position);
+            }
+        }
+        for space in &mut packing.spaces {
+            space.dimensions = self.extents(&space.dimensions);
+            space.position = self.outward(&space.position);
+        }
+        packing
+    }
+
+    /// `unpacked` with its item's allowed rotations back along the
+    /// container's own axes.
+    pub(crate) fn unpacked(&self, unpacked: Unpacked) -> Unpacked {
+        Unpacked { item: Frame::map_rotations(&unpacked.item, |rotation| self.outward(rotation)), ..unpacked }
+    }
+}
@@ -0,0 +1,73 @@
+use std::cmp::Ordering;
+use std::ops::Index;
+
+/// An `[x, y, z]` triple, used both for extents and for positions.
+pub type Coordinates = [f64; 3];
+
+/// Lexicographic comparison of two coordinate triples.
+pub fn cmp_coordinates(a: &Coordinates, b: &Coordinates) -> Ordering {
+    if a[0] < b[0] {
+        return Ordering::Less;
+    }
+    if a[0] > b[0] {
+        return Ordering::Greater;
+    }
+    if a[1] < b[1] {
+        return Ordering::Less;
+    }
+    if a[1] > b[1] {
+        return Ordering::Greater;
+    }
+    if a[2] < b[2] {
+        return Ordering::Less;
+    }
+    if a[2] > b[2] {
+        return Ordering::Greater;
+    }
+    Ordering::Equal
+}
+
+/// Box extents, kept both in the order given and sorted so that
+/// `length >= width >= height`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimensions {
+    pub dimensions: Coordinates,
+    pub length: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Dimensions {
+    pub fn from_array(array: &Coordinates) -> Dimensions {
+        let mut sorted = *array;
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Dimensions {
+            dimensions: *array,
+            length: sorted[2],
+            width: sorted[1],
+            height: sorted[0],
+        }
+    }
+
+    pub fn volume(&self) -> f64 {
+        self.length * self.width * self.height
+    }
+
+    /// Compares by length, then width, then height.
+    pub fn cmp_lwh(&self, other: &Self) -> Ordering {
+        cmp_coordinates(&[self.length, self.width, self.height], &[other.length, other.width, other.height])
+    }
+
+    /// Compares by height, then width, then length.
+    pub fn cmp_hwl(&self, other: &Self) -> Ordering {
+        cmp_coordinates(&[self.height, self.width, self.length], &[other.height, other.width, other.length])
+    }
+}
+
+impl Index<usize> for Dimensions {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.dimensions[index]
+    }
+}
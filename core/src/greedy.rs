@@ -0,0 +1,107 @@
+use crate::balance::centre_load;
+use crate::geometry::Dimensions;
+use crate::item::{Container, Item};
+use crate::options::PackOptions;
+use crate::packing::{Packing, Placement, Rotation, Space};
+use crate::support::{record_support, Stability};
+
+/// The box obtained by stacking every item flat on top of each other.
+pub fn item_greedy_box(items: &[Item]) -> Dimensions {
+    let mut max_length: f64 = 0.0;
+    let mut max_width: f64 = 0.0;
+    let mut total_height: f64 = 0.0;
+    for item in items.iter() {
+        max_length = max_length.max(item.dimensions.length);
+        max_width = max_width.max(item.dimensions.width);
+        total_height += item.dimensions.height;
+    }
+    Dimensions::from_array(&[max_length, max_width, 0.1 * (10.0 * total_height).round()])
+}
+
+/// Whether `container` can hold the greedy box of `items`, weight included.
+pub fn check_container_is_bigger_than_greedy_box(container: &Container, items: &[Item]) -> bool {
+    let greedy_box = item_greedy_box(items);
+    let mut weight = 0.0;
+    for item in items {
+        weight += item.weight.unwrap_or(0.0);
+    }
+    container.dimensions.length >= greedy_box[0]
+        && container.dimensions.width >= greedy_box[1]
+        && container.dimensions.height >= greedy_box[2]
+        && container.allows(weight)
+}
+
+/// The rotation laying `item` flat: its longest side along the container's
+/// length and its shortest along the height.
+fn flat_rotation(item: &Item) -> Rotation {
+    let mut rotation = [0, 1, 2];
+    rotation.sort_by(|&a, &b| item.dimensions[b].total_cmp(&item.dimensions[a]));
+    rotation
+}
+
+/// Every item laid flat on top of the one before, from the floor up.
+fn stacked(items: &[Item]) -> Vec<Placement> {
+    let mut height = 0.0;
+    let mut placements: Vec<Placement> = Vec::with_capacity(items.len());
+    for item in items {
+        let placement = Placement::new(item, flat_rotation(item), [0.0, 0.0, height]);
+        height += placement.dimensions.dimensions[2];
+        placements.push(placement);
+    }
+    placements
+}
+
+/// A single packing stacking every item flat on top of each other.
+pub fn generate_packing_for_greedy_box(items: &[Item]) -> Packing {
+    let weight = items.iter().map(|item| item.weight.unwrap_or(0.0)).sum();
+    let greedy_box = item_greedy_box(items);
+    Packing {
+        placements: stacked(items),
+        spaces: vec![],
+        weight,
+        volume: greedy_box.volume(),
+        outer_dimensions: greedy_box,
+        tare_weight: 0.0,
+        container_type: 0,
+        billable_weight: None,
+    }
+}
+
+/// The items stacked flat on top of each other in one `container`, `None`
+/// if there are none or unless every item fits inside it in an allowed
+/// rotation, the weight limit holds and each placement passes the stacking,
+/// support and balance limits of `options`.
+pub(crate) fn stack(container: &Container, items: &[Item], options: &PackOptions) -> Option<Packing> {
+    if items.is_empty() {
+        return None;
+    }
+    let bounds = container.dimensions.dimensions;
+    let stability = Stability::new(container, options);
+    let mut packing = Packing::new(container);
+    for (item, placement) in items.iter().zip(stacked(items)) {
+        let extents = placement.dimensions.dimensions;
+        let fits = (0..3).all(|axis| placement.position[axis] + extents[axis] <= bounds[axis] + 1e-9);
+        packing.weight += item.weight.unwrap_or(0.0);
+        if !fits
+            || !item.allows_rotation(&placement.rotation)
+            || !container.allows(packing.weight)
+            || !stability.allows(&packing.placements, &placement)
+        {
+            return None;
+        }
+        packing.placements.push(placement);
+    }
+
+    let top = packing.placements.last().map_or(0.0, |placement| placement.position[2] + placement.dimensions.dimensions[2]);
+    if top < bounds[2] {
+        packing.spaces.push(Space {
+            dimensions: Dimensions::from_array(&[bounds[0], bounds[1], bounds[2] - top]),
+            position: [0.0, 0.0, top],
+        });
+    }
+    record_support(&mut packing.placements);
+    if options.cog_envelope.is_some() {
+        centre_load(&mut packing, &bounds);
+    }
+    Some(packing)
+}
@@ -0,0 +1,132 @@
+use crate::item::{Container, Item};
+use crate::layout::{first_fit_with, Layout};
+use crate::options::PackOptions;
+use crate::packing::Packing;
+use crate::place::rotations;
+use crate::unpacked::Unpacked;
+use std::time::{Duration, Instant};
+
+const START_TEMPERATURE: f64 = 0.1;
+const END_TEMPERATURE: f64 = 0.001;
+
+/// Small xorshift generator so runs can be repeated from a seed without
+/// pulling in a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        const MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+        // xorshift must not start from zero
+        Rng(if seed == MIX { MIX } else { seed ^ MIX })
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The order items are packed in and, per item, which of its
+/// [`rotations`] to try first (`None` leaves it to [`place`](crate::place)).
+#[derive(Clone)]
+struct Genome {
+    order: Vec<usize>,
+    rotations: Vec<Option<usize>>,
+}
+
+/// Lower is better: the number of containers, nudged down the fuller the
+/// fullest ones are so that emptying a container pays off gradually.
+pub(crate) fn cost(packings: &[Packing], errors: &[Unpacked], items: usize) -> f64 {
+    if packings.is_empty() {
+        return errors.len() as f64 * (items + 1) as f64;
+    }
+    let fill: f64 = packings
+        .iter()
+        .map(|packing| {
+            let fill = 1.0 - packing.free_volume() / packing.volume;
+            fill * fill
+        })
+        .sum();
+    errors.len() as f64 * (items + 1) as f64 + packings.len() as f64 - fill / packings.len() as f64
+}
+
+fn decode<L: Layout>(container: &Container, items: &[Item], genome: &Genome, options: &PackOptions) -> (Vec<Packing>, Vec<Unpacked>) {
+    let ordered: Vec<Item> = genome.order.iter().map(|&i| items[i].clone()).collect();
+    first_fit_with(Vec::new(), container, &ordered, options, |layout: &mut L, i, item, placements| {
+        let rotation = genome.rotations[genome.order[i]].map(|r| rotations(item)[r]);
+        layout.place_preferring(item, rotation.as_ref(), placements)
+    })
+}
+
+fn neighbour(genome: &Genome, rng: &mut Rng) -> Genome {
+    let mut next = genome.clone();
+    let n = next.order.len();
+    if n > 1 && rng.unit() < 0.5 {
+        let (a, b) = (rng.below(n), rng.below(n));
+        next.order.swap(a, b);
+    } else {
+        let i = rng.below(n);
+        let r = rng.below(7);
+        next.rotations[i] = if r == 6 { None } else { Some(r) };
+    }
+    next
+}
+
+/// Simulated annealing over the packing order and rotations of `items`,
+/// decoded with the layout `L`, until `time_limit` runs out.
+///
+/// Returns the best packing found if it beats `initial_cost`.
+pub(crate) fn improve<L: Layout>(
+    container: &Container,
+    items: &[Item],
+    options: &PackOptions,
+    initial_cost: f64,
+    time_limit: Duration,
+    seed: u64,
+) -> Option<(Vec<Packing>, Vec<Unpacked>)> {
+    if items.is_empty() {
+        return None;
+    }
+    let started = Instant::now();
+    let mut rng = Rng::new(seed);
+    let mut current = Genome { order: (0..items.len()).collect(), rotations: vec![None; items.len()] };
+    let (packings, errors) = decode::<L>(container, items, &current, options);
+    let mut current_cost = cost(&packings, &errors, items.len());
+    let mut best: Option<(f64, Vec<Packing>, Vec<Unpacked>)> = None;
+    if current_cost < initial_cost {
+        best = Some((current_cost, packings, errors));
+    }
+
+    loop {
+        let elapsed = started.elapsed();
+        if elapsed >= time_limit {
+            break;
+        }
+        let progress = elapsed.as_secs_f64() / time_limit.as_secs_f64();
+        let temperature = START_TEMPERATURE * (END_TEMPERATURE / START_TEMPERATURE).powf(progress);
+
+        let candidate = neighbour(&current, &mut rng);
+        let (packings, errors) = decode::<L>(container, items, &candidate, options);
+        let candidate_cost = cost(&packings, &errors, items.len());
+        let delta = candidate_cost - current_cost;
+        if delta <= 0.0 || rng.unit() < (-delta / temperature).exp() {
+            current = candidate;
+            current_cost = candidate_cost;
+        }
+        let best_cost = best.as_ref().map_or(initial_cost, |(best_cost, _, _)| *best_cost);
+        if candidate_cost < best_cost {
+            best = Some((candidate_cost, packings, errors));
+        }
+    }
+    best.map(|(_, packings, errors)| (packings, errors))
+}
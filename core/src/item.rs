@@ -0,0 +1,193 @@
+use crate::error::{self, PackError};
+use crate::geometry::Dimensions;
+use crate::packing::Rotation;
+use std::fmt;
+
+/// A box to be packed.
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub dimensions: Dimensions,
+    pub weight: Option<f64>,
+    /// The only rotations the item may be placed in, any if `None`.
+    pub allowed_rotations: Option<Vec<Rotation>>,
+    /// Most weight that may rest on the item, directly or not. Any if `None`.
+    pub max_load: Option<f64>,
+    /// Whether anything may be placed directly on top of the item.
+    pub stackable: bool,
+    /// Identifies the item, or the stock unit it is one of, in placements
+    /// and errors.
+    pub id: Option<String>,
+    /// Which of the [`copies`](Self::copies) of an item this is.
+    pub instance: usize,
+}
+
+impl Item {
+    pub fn new(dimensions: [f64; 3], weight: Option<f64>) -> Item {
+        Item {
+            dimensions: Dimensions::from_array(&dimensions),
+            weight,
+            allowed_rotations: None,
+            max_load: None,
+            stackable: true,
+            id: None,
+            instance: 0,
+        }
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Item {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// `quantity` copies of the item, numbered from 0 by their
+    /// [`instance`](Self::instance).
+    ///
+    /// ```
+    /// use easy_box_packer_core::Item;
+    ///
+    /// let copies = Item::new([1.0, 2.0, 3.0], None).with_id("SKU-123").copies(3);
+    /// assert_eq!(copies.iter().map(|item| item.instance).collect::<Vec<_>>(), vec![0, 1, 2]);
+    /// ```
+    pub fn copies(&self, quantity: usize) -> Vec<Item> {
+        (0..quantity).map(|instance| Item { instance, ..self.clone() }).collect()
+    }
+
+    /// Checks the item as [`pack`](crate::pack) does, reporting it at
+    /// `index`.
+    pub fn check(&self, index: usize) -> Result<(), PackError> {
+        error::check_item(Some(index), self)
+    }
+
+    /// Limits the weight that may rest on the item.
+    pub fn with_max_load(mut self, max_load: f64) -> Item {
+        self.max_load = Some(max_load);
+        self
+    }
+
+    /// Keeps anything from being placed on top of the item.
+    pub fn not_stackable(mut self) -> Item {
+        self.stackable = false;
+        self
+    }
+
+    /// Restricts the item to `rotations`.
+    pub fn with_allowed_rotations(mut self, rotations: Vec<Rotation>) -> Item {
+        self.allowed_rotations = Some(rotations);
+        self
+    }
+
+    /// Keeps the item's last dimension along the container's height, letting
+    /// it turn only around the vertical axis.
+    ///
+    /// ```
+    /// use easy_box_packer_core::Item;
+    ///
+    /// let item = Item::new([4.0, 3.0, 2.0], None).keep_upright();
+    /// assert!(item.allows_rotation(&[1, 0, 2]));
+    /// assert!(!item.allows_rotation(&[2, 1, 0]));
+    /// ```
+    pub fn keep_upright(mut self) -> Item {
+        let upright = |rotation: &Rotation| rotation[2] == 2;
+        self.allowed_rotations = Some(match self.allowed_rotations {
+            Some(mut rotations) => {
+                rotations.retain(upright);
+                rotations
+            }
+            None => vec![[0, 1, 2], [1, 0, 2]],
+        });
+        self
+    }
+
+    /// Whether the item may be placed turned by `rotation`.
+    pub fn allows_rotation(&self, rotation: &Rotation) -> bool {
+        self.allowed_rotations.as_ref().is_none_or(|rotations| rotations.contains(rotation))
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        if let Some(id) = &self.id {
+            write!(f, ":id=>{:?}, :instance=>{}, ", id, self.instance)?;
+        }
+        write!(f, ":dimensions=>[{}, {}, {}]", self.dimensions[0], self.dimensions[1], self.dimensions[2])?;
+        if let Some(w) = self.weight {
+            write!(f, ", weight: {}", w)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// How much weight a single container may hold.
+///
+/// ```
+/// use easy_box_packer_core::WeightLimit;
+///
+/// assert!(WeightLimit::Unlimited.allows(1000.0));
+/// assert!(WeightLimit::Bounded(50.0).allows(50.0));
+/// assert!(!WeightLimit::Bounded(50.0).allows(50.5));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WeightLimit {
+    #[default]
+    Unlimited,
+    Bounded(f64),
+}
+
+impl WeightLimit {
+    /// Whether a container holding `weight` stays within the limit.
+    pub fn allows(&self, weight: f64) -> bool {
+        match *self {
+            WeightLimit::Unlimited => true,
+            WeightLimit::Bounded(limit) => weight <= limit,
+        }
+    }
+
+    /// The limit, `None` if unlimited.
+    pub fn bound(&self) -> Option<f64> {
+        match *self {
+            WeightLimit::Unlimited => None,
+            WeightLimit::Bounded(limit) => Some(limit),
+        }
+    }
+}
+
+/// The box items are packed into. As many copies as needed are opened.
+#[derive(Clone, Debug)]
+pub struct Container {
+    /// Inner size, what the items are packed into.
+    pub dimensions: Dimensions,
+    /// Outer size, what carriers measure. The inner size unless given.
+    pub outer_dimensions: Dimensions,
+    /// Weight of the empty container, counted against the weight limit.
+    pub tare_weight: f64,
+    pub weight_limit: WeightLimit,
+}
+
+impl Container {
+    pub fn new(dimensions: [f64; 3], weight_limit: WeightLimit) -> Container {
+        let dimensions = Dimensions::from_array(&dimensions);
+        Container { outer_dimensions: dimensions.clone(), dimensions, tare_weight: 0.0, weight_limit }
+    }
+
+    pub fn with_outer_dimensions(mut self, outer_dimensions: [f64; 3]) -> Container {
+        self.outer_dimensions = Dimensions::from_array(&outer_dimensions);
+        self
+    }
+
+    pub fn with_tare_weight(mut self, tare_weight: f64) -> Container {
+        self.tare_weight = tare_weight;
+        self
+    }
+
+    /// Whether the container stays within its weight limit holding items
+    /// of `weight`, its own weight included.
+    pub fn allows(&self, weight: f64) -> bool {
+        self.weight_limit.allows(self.tare_weight + weight)
+    }
+
+    /// The most the items in the container may weigh, `None` if unlimited.
+    pub fn payload_limit(&self) -> Option<f64> {
+        self.weight_limit.bound().map(|limit| (limit - self.tare_weight).max(0.0))
+    }
+}
@@ -0,0 +1,195 @@
+use crate::geometry::{Coordinates, Dimensions};
+use crate::item::{Container, Item};
+use crate::layout::first_fit_into;
+use crate::options::PackOptions;
+use crate::packing::{Packing, Placement, Rotation, Space};
+use crate::place::{allowed_rotations, rotate, GuillotineSpaces};
+use crate::support::Stability;
+use crate::unpacked::Unpacked;
+use std::cmp::Ordering;
+
+/// A container being filled bottom up with whole layers.
+struct Stack {
+    packing: Packing,
+    /// Height at which the next layer starts.
+    top: f64,
+    /// Room left beside the layers already built.
+    spaces: Vec<Space>,
+}
+
+/// How one group of identical items is laid out in a layer.
+struct Pattern {
+    extents: Coordinates,
+    /// Items along the container's length and width.
+    rows: usize,
+    columns: usize,
+}
+
+impl Pattern {
+    fn per_layer(&self) -> usize {
+        self.rows * self.columns
+    }
+}
+
+/// The rotation of `item` fitting the most whole layers of it into the
+/// `footprint` and `height` available, preferring fuller layers. A layer
+/// covers as much of the footprint as the rotation allows, so a group of
+/// `count` items too few to fill one gets no pattern.
+fn best_pattern(item: &Item, footprint: [f64; 2], height: f64, count: usize) -> Option<(Pattern, usize)> {
+    let mut best: Option<(Pattern, usize)> = None;
+    for rotation in allowed_rotations(item).iter() {
+        let extents = rotate(&item.dimensions, rotation);
+        // counted in floats, as tiny items may overflow the count of a layer
+        let (rows, columns) = ((footprint[0] / extents[0]).floor(), (footprint[1] / extents[1]).floor());
+        if rows * columns == 0.0 || rows * columns > count as f64 {
+            continue;
+        }
+        let pattern = Pattern { extents, rows: rows as usize, columns: columns as usize };
+        let layers = ((height / extents[2]).floor() as usize).min(count / pattern.per_layer());
+        if layers == 0 {
+            continue;
+        }
+        let better = match &best {
+            None => true,
+            Some((other, other_layers)) => match (layers * pattern.per_layer()).cmp(&(other_layers * other.per_layer())) {
+                Ordering::Equal => pattern.per_layer() > other.per_layer(),
+                ordering => ordering == Ordering::Greater,
+            },
+        };
+        if better {
+            best = Some((pattern, layers));
+        }
+    }
+    best
+}
+
+/// Whether `a` and `b` can be laid out alike: items free to turn only need
+/// the same sides, in any order.
+fn same_shape(a: &Item, b: &Item) -> bool {
+    let sides = |item: &Item| match item.allowed_rotations {
+        Some(_) => item.dimensions.dimensions,
+        None => [item.dimensions.length, item.dimensions.width, item.dimensions.height],
+    };
+    sides(a) == sides(b) && a.allowed_rotations == b.allowed_rotations
+}
+
+/// The rotation turning `item` to `extents`, one of the same shape as the
+/// item the pattern was made for.
+fn rotation_to(item: &Item, extents: &Coordinates) -> Rotation {
+    *allowed_rotations(item)
+        .iter()
+        .find(|rotation| rotate(&item.dimensions, rotation) == *extents)
+        .expect("items of the same shape turn to the same extents")
+}
+
+fn space(position: Coordinates, end: Coordinates) -> Space {
+    Space { dimensions: Dimensions::from_array(&[end[0] - position[0], end[1] - position[1], end[2] - position[2]]), position }
+}
+
+impl Stack {
+    fn new(container: &Container) -> Self {
+        Stack {
+            packing: Packing::new(container),
+            top: 0.0,
+            spaces: vec![],
+        }
+    }
+
+    /// Stacks as many whole layers of `group` as fit, taking the items used
+    /// off its front. Returns whether any layer was built.
+    fn build(&mut self, container: &Container, group: &mut Vec<Item>, stability: &Stability) -> bool {
+        let bounds = container.dimensions.dimensions;
+        let (pattern, most_layers) = match best_pattern(&group[0], [bounds[0], bounds[1]], bounds[2] - self.top, group.len()) {
+            Some(best) => best,
+            None => return false,
+        };
+        let per_layer = pattern.per_layer();
+        let [a, b, h] = pattern.extents;
+
+        // a layer goes in whole or not at all, so stop at the first one that
+        // breaks the weight limit or overloads the items beneath
+        let mut placements = self.packing.placements.clone();
+        let mut weight = self.packing.weight;
+        let mut layers = 0;
+        'layers: while layers < most_layers {
+            let mut trial = placements.clone();
+            let mut trial_weight = weight;
+            for (in_layer, item) in group[layers * per_layer..(layers + 1) * per_layer].iter().enumerate() {
+                let position = [(in_layer / pattern.columns) as f64 * a, (in_layer % pattern.columns) as f64 * b, self.top + layers as f64 * h];
+                let placement = Placement::new(item, rotation_to(item, &pattern.extents), position);
+                trial_weight += item.weight.unwrap_or(0.0);
+                if !container.allows(trial_weight) || !stability.allows(&trial, &placement) {
+                    break 'layers;
+                }
+                trial.push(placement);
+            }
+            placements = trial;
+            weight = trial_weight;
+            layers += 1;
+        }
+        if layers == 0 {
+            return false;
+        }
+        group.drain(..layers * per_layer);
+        self.packing.placements = placements;
+        self.packing.weight = weight;
+
+        let (used_length, used_width) = (pattern.rows as f64 * a, pattern.columns as f64 * b);
+        let bottom = self.top;
+        self.top += layers as f64 * h;
+        self.spaces.push(space([used_length, 0.0, bottom], [bounds[0], bounds[1], self.top]));
+        self.spaces.push(space([0.0, used_width, bottom], [used_length, bounds[1], self.top]));
+        true
+    }
+
+    fn into_open(mut self, container: &Container, options: &PackOptions) -> (Packing, GuillotineSpaces) {
+        let bounds = container.dimensions.dimensions;
+        self.spaces.push(space([0.0, 0.0, self.top], bounds));
+        self.spaces.retain(|space| space.volume() > 0.0);
+        (self.packing, GuillotineSpaces::from_spaces(self.spaces, container, options))
+    }
+}
+
+/// Builds whole layers of identical items bottom up, then fits whatever is
+/// left over around and on top of them using free spaces.
+///
+/// `items` must be sorted so that items of the same shape are next to each
+/// other.
+pub(crate) fn pack_layers(container: &Container, items: &[Item], options: &PackOptions) -> (Vec<Packing>, Vec<Unpacked>) {
+    let stability = Stability::new(container, options);
+    let mut stacks: Vec<Stack> = Vec::new();
+    let mut rest: Vec<Item> = Vec::new();
+
+    let mut start = 0;
+    while start < items.len() {
+        let mut end = start + 1;
+        while end < items.len() && same_shape(&items[end], &items[start]) {
+            end += 1;
+        }
+        // too heavy items are reported by the free space packing
+        let (mut group, too_heavy): (Vec<Item>, Vec<Item>) = items[start..end]
+            .iter()
+            .cloned()
+            .partition(|item| container.allows(item.weight.unwrap_or(0.0)));
+        rest.extend(too_heavy);
+        start = end;
+
+        let mut i = 0;
+        while !group.is_empty() {
+            if i == stacks.len() {
+                let mut stack = Stack::new(container);
+                if !stack.build(container, &mut group, &stability) {
+                    break;
+                }
+                stacks.push(stack);
+            } else {
+                stacks[i].build(container, &mut group, &stability);
+            }
+            i += 1;
+        }
+        rest.extend(group);
+    }
+
+    let open = stacks.into_iter().map(|stack| stack.into_open(container, options)).collect();
+    first_fit_into(open, container, &rest, options)
+}
@@ -0,0 +1,136 @@
+use crate::balance::centre_load;
+use crate::item::{Container, Item};
+use crate::options::PackOptions;
+use crate::packing::{Packing, Placement, Rotation, Space};
+use crate::support::record_support;
+use crate::unpacked::Unpacked;
+
+/// Bookkeeping of the free room inside one opened container, deciding where
+/// the next item goes.
+pub(crate) trait Layout {
+    fn new(container: &Container, options: &PackOptions) -> Self;
+
+    /// Finds room for `item` next to `placements` (everything already in the
+    /// container) and claims it, or returns `None` if the item does not fit.
+    fn place(&mut self, item: &Item, placements: &[Placement]) -> Option<Placement>;
+
+    /// [`place`](Self::place), turning the item by `rotation` if it fits
+    /// anywhere that way.
+    fn place_preferring(&mut self, item: &Item, rotation: Option<&Rotation>, placements: &[Placement]) -> Option<Placement> {
+        let turned = rotation.filter(|rotation| item.allows_rotation(rotation)).and_then(|rotation| {
+            let turned = Item { allowed_rotations: Some(vec![*rotation]), ..item.clone() };
+            self.place(&turned, placements)
+        });
+        turned.or_else(|| self.place(item, placements))
+    }
+
+    /// The free room left next to `placements`, as reported in
+    /// [`Packing::spaces`].
+    fn spaces(&self, placements: &[Placement]) -> Vec<Space>;
+}
+
+/// Puts every item (in the order given) into the first opened container it
+/// fits in, opening a new one when none does.
+pub(crate) fn first_fit<L: Layout>(container: &Container, items: &[Item], options: &PackOptions) -> (Vec<Packing>, Vec<Unpacked>) {
+    first_fit_into::<L>(Vec::new(), container, items, options)
+}
+
+/// [`first_fit`] starting from containers that are already partly filled.
+pub(crate) fn first_fit_into<L: Layout>(
+    open: Vec<(Packing, L)>,
+    container: &Container,
+    items: &[Item],
+    options: &PackOptions,
+) -> (Vec<Packing>, Vec<Unpacked>) {
+    first_fit_with(open, container, items, options, |layout: &mut L, _, item, placements| layout.place(item, placements))
+}
+
+/// [`first_fit_into`] placing the `i`th item into a layout with
+/// `place(layout, i, item, placements)` instead of [`Layout::place`].
+pub(crate) fn first_fit_with<L, F>(
+    mut open: Vec<(Packing, L)>,
+    container: &Container,
+    items: &[Item],
+    options: &PackOptions,
+    mut place: F,
+) -> (Vec<Packing>, Vec<Unpacked>)
+where
+    L: Layout,
+    F: FnMut(&mut L, usize, &Item, &[Placement]) -> Option<Placement>,
+{
+    let mut errors: Vec<Unpacked> = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        let item_weight = item.weight.unwrap_or(0.0);
+        if !container.allows(item_weight) {
+            errors.push(Unpacked::too_heavy(container, item));
+            continue;
+        }
+        let mut item_has_been_packed = false;
+        for (packing, layout) in &mut open {
+            // If this packings going to be too big with this
+            // item as well then skip on to the next packing
+            if !container.allows(packing.weight + item_weight) {
+                continue;
+            }
+            if let Some(p) = place(layout, i, item, &packing.placements) {
+                packing.placements.push(p);
+                packing.weight += item_weight;
+                item_has_been_packed = true;
+                break;
+            }
+        }
+        if item_has_been_packed {
+            continue;
+        }
+
+        // Can't fit in any of the current packings so lets try a new
+        // container. If it can't be placed there, then it's just too
+        // big for the container and we should abandon hope
+        let mut layout = L::new(container, options);
+        match place(&mut layout, i, item, &[]) {
+            None => errors.push(Unpacked::classify(container, item)),
+            Some(p) => {
+                let mut packing = Packing::new(container);
+                packing.placements.push(p);
+                packing.weight = item_weight;
+                open.push((packing, layout));
+            }
+        }
+    }
+
+    let packings = open.into_iter().map(|(packing, layout)| finish(packing, &layout, container, options)).collect();
+    (packings, errors)
+}
+
+/// Fills a single `container` with as many of `items` as fit, in order.
+/// Returns the packing and the indices of the items packed.
+pub(crate) fn fill<L: Layout>(container: &Container, items: &[Item], options: &PackOptions) -> (Packing, Vec<usize>) {
+    let mut layout = L::new(container, options);
+    let mut packing = Packing::new(container);
+    let mut packed = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let item_weight = item.weight.unwrap_or(0.0);
+        if !container.allows(packing.weight + item_weight) {
+            continue;
+        }
+        if let Some(p) = layout.place(item, &packing.placements) {
+            packing.placements.push(p);
+            packing.weight += item_weight;
+            packed.push(i);
+        }
+    }
+    (finish(packing, &layout, container, options), packed)
+}
+
+/// Reports the free room left in `packing` and how its items bear on each
+/// other, balancing the load if asked to.
+pub(crate) fn finish<L: Layout>(mut packing: Packing, layout: &L, container: &Container, options: &PackOptions) -> Packing {
+    packing.spaces = layout.spaces(&packing.placements);
+    record_support(&mut packing.placements);
+    if options.cog_envelope.is_some() {
+        let bounds = container.dimensions.dimensions;
+        centre_load(&mut packing, &bounds);
+    }
+    packing
+}
@@ -0,0 +1,149 @@
+//! 3D bin-packing with weight limits.
+//!
+//! Items are sorted by their longest side and packed first-fit into as many
+//! copies of a [`Container`] as needed. Each opened container keeps a list of
+//! free [`Space`]s which are split up around every item placed into them.
+//!
+//! ```
+//! use easy_box_packer_core::{pack, Container, Item, WeightLimit};
+//!
+//! let container = Container::new([10.0, 10.0, 10.0], WeightLimit::Bounded(50.0));
+//! let items = vec![Item::new([5.0, 5.0, 5.0], Some(5.0)); 8];
+//! let result = pack(&container, &items).unwrap();
+//! assert_eq!(result.packings.len(), 1);
+//! assert!(result.errors.is_empty());
+//! ```
+
+mod balance;
+mod bounds;
+mod carrier;
+mod container_types;
+mod design;
+mod error;
+mod exact;
+mod geometry;
+mod greedy;
+mod improve;
+mod extreme_points;
+mod frame;
+mod item;
+mod layers;
+mod layout;
+mod maximal_spaces;
+mod options;
+mod packing;
+mod place;
+mod smallest;
+mod support;
+mod unpacked;
+mod validate;
+
+pub use crate::bounds::{lower_bounds, LowerBounds};
+pub use crate::carrier::{pack_for_carrier, Carrier, Rate};
+pub use crate::container_types::{pack_container_types, ContainerType};
+pub use crate::design::{design_box, BoxSpec, DesignedBox};
+pub use crate::error::PackError;
+pub use crate::geometry::{cmp_coordinates, Coordinates, Dimensions};
+pub use crate::greedy::{check_container_is_bigger_than_greedy_box, generate_packing_for_greedy_box, item_greedy_box};
+pub use crate::item::{Container, Item, WeightLimit};
+pub use crate::options::{Merit, PackOptions, Strategy};
+pub use crate::packing::{PackResult, Packing, Placement, Rotation, Space};
+pub use crate::place::{allowed_rotations, break_up_space, cmp_dimensions_and_position, place, rotate, rotations};
+pub use crate::smallest::find_smallest_containers;
+pub use crate::unpacked::{Reason, Unpacked};
+pub use crate::validate::{validate, validate_with_options, Violation};
+
+use crate::exact::pack_exact;
+use crate::extreme_points::ExtremePoints;
+use crate::frame::Frame;
+use crate::greedy::stack;
+use crate::improve::improve;
+use crate::layers::pack_layers;
+use crate::layout::first_fit;
+use crate::maximal_spaces::MaximalSpaces;
+use crate::place::GuillotineSpaces;
+use std::time::Duration;
+
+/// Packs `items` into as few copies of `container` as the first-fit
+/// heuristic manages. Items that can never fit are reported in
+/// [`PackResult::errors`].
+///
+/// Fails if any dimension is not a positive number or any weight is
+/// negative or not finite.
+pub fn pack(container: &Container, items: &[Item]) -> Result<PackResult, PackError> {
+    pack_with_options(container, items, &PackOptions::default())
+}
+
+/// [`pack`] with non-default [`PackOptions`]. Also fails if
+/// [`PackOptions::min_support`] is not between 0 and 1 or the
+/// [`PackOptions::cog_envelope`] has negative sides.
+pub fn pack_with_options(container: &Container, items: &[Item], options: &PackOptions) -> Result<PackResult, PackError> {
+    error::check_input(container, items)?;
+    error::check_options(options)?;
+    let frame = Frame::new(container, options);
+    let mut result = pack_checked(&frame.container(container), frame.items(items), &frame.options(options));
+    result.packings = result.packings.into_iter().map(|packing| frame.packing(packing, options)).collect();
+    result.unpacked = result.unpacked.into_iter().map(|unpacked| frame.unpacked(unpacked)).collect();
+    Ok(result)
+}
+
+/// Packs checked `items` as [`pack_with_options`] does, with their rotations
+/// and the `options` along the container's packing axes.
+fn pack_checked(container: &Container, mut items: Vec<Item>, options: &PackOptions) -> PackResult {
+
+    // so by length first (biggest) and then sort in descending order
+    items.sort_by(|a, b| b.dimensions.cmp_lwh(&a.dimensions));
+    let stacked = if options.strategy == Strategy::GreedyBox { stack(container, &items, options) } else { None };
+    let (mut packings, mut unpacked, mut strategy) = match stacked {
+        Some(packing) => (vec![packing], vec![], Strategy::GreedyBox),
+        None => {
+            let (packings, unpacked) = match options.strategy {
+                Strategy::Spaces | Strategy::GreedyBox => first_fit::<GuillotineSpaces>(container, &items, options),
+                Strategy::ExtremePoints(_) => first_fit::<ExtremePoints>(container, &items, options),
+                Strategy::MaximalSpaces => first_fit::<MaximalSpaces>(container, &items, options),
+                Strategy::Layers => pack_layers(container, &items, options),
+                Strategy::Exact => pack_exact(container, &items, options),
+            };
+            // a stack that does not fit is packed with free spaces instead
+            let strategy = if options.strategy == Strategy::GreedyBox { Strategy::Spaces } else { options.strategy };
+            (packings, unpacked, strategy)
+        }
+    };
+
+    // the exact search already spends the time limit, the stack is final
+    let improvable = strategy != Strategy::Exact && strategy != Strategy::GreedyBox;
+    if let Some(time_limit_ms) = options.time_limit_ms.filter(|_| improvable) {
+        let initial_cost = improve::cost(&packings, &unpacked, items.len());
+        let time_limit = Duration::from_millis(time_limit_ms);
+        let seed = options.seed;
+        let better = match strategy {
+            Strategy::ExtremePoints(_) => improve::<ExtremePoints>(container, &items, options, initial_cost, time_limit, seed),
+            Strategy::MaximalSpaces => improve::<MaximalSpaces>(container, &items, options, initial_cost, time_limit, seed),
+            _ => improve::<GuillotineSpaces>(container, &items, options, initial_cost, time_limit, seed),
+        };
+        if let Some(better) = better {
+            packings = better.0;
+            unpacked = better.1;
+            // layers are not searched over, the better packing has free spaces
+            if strategy == Strategy::Layers {
+                strategy = Strategy::Spaces;
+            }
+        }
+    }
+
+    // one stack beats several containers, if it passes the same checks
+    // stacks have no free spaces for legacy positions to report
+    if options.greedy_fallback && !options.legacy_positions && packings.len() > 1 {
+        if let Some(packing) = stack(container, &items, options) {
+            packings = vec![packing];
+            unpacked.clear();
+            strategy = Strategy::GreedyBox;
+        }
+    }
+
+    let lower_bounds = lower_bounds(container, &items);
+    // a lower bound on containers says nothing about items left out
+    let proven_optimal = unpacked.is_empty() && packings.len() == lower_bounds.best();
+    let errors = unpacked.iter().map(|unpacked| unpacked.to_string()).collect();
+    PackResult { packings, errors, unpacked, strategy, lower_bounds: Some(lower_bounds), proven_optimal, cost: None }
+}
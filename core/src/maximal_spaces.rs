@@ -0,0 +1,106 @@
+use crate::geometry::{cmp_coordinates, Coordinates, Dimensions};
+use crate::item::{Container, Item};
+use crate::layout::Layout;
+use crate::options::PackOptions;
+use crate::packing::{Placement, Space};
+use crate::place::place;
+use crate::support::Stability;
+
+/// The free room of a container as every maximal empty box. Unlike
+/// [`GuillotineSpaces`](crate::place::GuillotineSpaces) these overlap, so an
+/// item can use room that a guillotine cut would have split in two.
+pub(crate) struct MaximalSpaces {
+    spaces: Vec<Space>,
+    stability: Stability,
+}
+
+fn end(space: &Space) -> Coordinates {
+    [
+        space.position[0] + space.dimensions[0],
+        space.position[1] + space.dimensions[1],
+        space.position[2] + space.dimensions[2],
+    ]
+}
+
+fn from_corners(start: Coordinates, end: Coordinates) -> Space {
+    Space { dimensions: Dimensions::from_array(&[end[0] - start[0], end[1] - start[1], end[2] - start[2]]), position: start }
+}
+
+fn intersects(space: &Space, placement: &Placement) -> bool {
+    let space_end = end(space);
+    (0..3).all(|axis| {
+        space.position[axis] < placement.position[axis] + placement.dimensions[axis]
+            && placement.position[axis] < space_end[axis]
+    })
+}
+
+fn contains(outer: &Space, inner: &Space) -> bool {
+    let (outer_end, inner_end) = (end(outer), end(inner));
+    (0..3).all(|axis| outer.position[axis] <= inner.position[axis] && inner_end[axis] <= outer_end[axis])
+}
+
+/// The up to six maximal boxes of `space` left on each side of `placement`.
+fn split(space: &Space, placement: &Placement) -> Vec<Space> {
+    let space_end = end(space);
+    let mut spaces = Vec::with_capacity(6);
+    for axis in 0..3 {
+        let placement_start = placement.position[axis];
+        let placement_end = placement_start + placement.dimensions[axis];
+        if placement_start > space.position[axis] {
+            let mut below = space_end;
+            below[axis] = placement_start;
+            spaces.push(from_corners(space.position, below));
+        }
+        if placement_end < space_end[axis] {
+            let mut above = space.position;
+            above[axis] = placement_end;
+            spaces.push(from_corners(above, space_end));
+        }
+    }
+    spaces
+}
+
+impl Layout for MaximalSpaces {
+    fn new(container: &Container, options: &PackOptions) -> Self {
+        let space = Space {
+            dimensions: container.dimensions.clone(),
+            position: [0.0, 0.0, 0.0],
+        };
+        MaximalSpaces { spaces: vec![space], stability: Stability::new(container, options) }
+    }
+
+    fn place(&mut self, item: &Item, placements: &[Placement]) -> Option<Placement> {
+        // lowest first, then closest to the back and to the left
+        self.spaces.sort_by(|a, b| {
+            cmp_coordinates(&[a.position[2], a.position[1], a.position[0]], &[b.position[2], b.position[1], b.position[0]])
+                .then_with(|| a.dimensions.cmp_hwl(&b.dimensions))
+        });
+        let placement = self.spaces.iter().find_map(|space| place(item, space).filter(|p| self.stability.allows(placements, p)))?;
+
+        let mut spaces: Vec<Space> = Vec::with_capacity(self.spaces.len());
+        for space in self.spaces.drain(..) {
+            if intersects(&space, &placement) {
+                spaces.extend(split(&space, &placement));
+            } else {
+                spaces.push(space);
+            }
+        }
+        spaces.retain(|space| space.volume() > 0.0);
+        // keep only maximal spaces, and only one of any duplicates
+        let mut maximal: Vec<Space> = Vec::with_capacity(spaces.len());
+        for (i, space) in spaces.iter().enumerate() {
+            let swallowed = spaces.iter().enumerate().any(|(j, other)| {
+                j != i && contains(other, space) && (!contains(space, other) || j < i)
+            });
+            if !swallowed {
+                maximal.push(space.clone());
+            }
+        }
+        self.spaces = maximal;
+        Some(placement)
+    }
+
+    fn spaces(&self, _placements: &[Placement]) -> Vec<Space> {
+        self.spaces.clone()
+    }
+}
@@ -0,0 +1,97 @@
+use crate::geometry::Coordinates;
+
+/// How items are placed inside an opened container.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Strategy {
+    /// Keep the free room as disjoint spaces and split the space an item is
+    /// placed in into three.
+    #[default]
+    Spaces,
+    /// Place items on the extreme points created by the projections of the
+    /// items already placed, choosing the point by the given merit.
+    ExtremePoints(Merit),
+    /// Keep every maximal empty box as a free space, even where they
+    /// overlap, and place items into the lowest one they fit.
+    MaximalSpaces,
+    /// Build whole layers of identical items with the rotation filling the
+    /// container's floor best, then place the rest, including items too few
+    /// to fill a layer, as with `Spaces`.
+    Layers,
+    /// Search item orders and rotations for the fewest containers, starting
+    /// from what `Spaces` finds. Meant for small orders; the search is capped
+    /// by [`PackOptions::node_limit`] and [`PackOptions::time_limit_ms`].
+    Exact,
+    /// Stack every item flat on top of each other in a single container,
+    /// the longest side along the container's length. Items are placed as
+    /// with `Spaces` when the stack does not fit.
+    GreedyBox,
+}
+
+/// Ranks candidate extreme points for an item, lower is better.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Merit {
+    /// Sum of the room left along each axis between the item and whatever
+    /// blocks that axis.
+    #[default]
+    ResidualSpace,
+    /// Volume of the residual space at the point not taken up by the item.
+    FreeVolume,
+}
+
+/// Tuning knobs for [`pack_with_options`](crate::pack_with_options).
+#[derive(Clone, Debug)]
+pub struct PackOptions {
+    pub strategy: Strategy,
+    /// Report each placement's position as the extents of the free space it
+    /// was placed in instead of its origin, as earlier releases did. Only
+    /// [`Strategy::Spaces`] and [`Strategy::Exact`] place everything into
+    /// such spaces, so other strategies and [`given_axes`](Self::given_axes)
+    /// are turned away, and no [`greedy_fallback`](Self::greedy_fallback)
+    /// stack is tried. Only the result is rewritten; support, stacking and
+    /// balance are worked out from the origins.
+    pub legacy_positions: bool,
+    /// When set, spend up to this many milliseconds searching for a better
+    /// packing order and item rotations with simulated annealing, or with
+    /// [`Strategy::Exact`] at most this long in its search. Items are placed
+    /// as the strategy places them, except that [`Strategy::Layers`] is
+    /// searched with free spaces and reported as [`Strategy::Spaces`] if
+    /// that finds a better packing.
+    pub time_limit_ms: Option<u64>,
+    /// Most nodes [`Strategy::Exact`] visits, 100 000 when not set.
+    pub node_limit: Option<u64>,
+    /// Share of an item's base, between 0 and 1, that must rest on the
+    /// container floor or on the tops of other items. Items may overhang
+    /// freely when not set.
+    pub min_support: Option<f64>,
+    /// How far each packing's centre of gravity may lie from the container's
+    /// centre along its length, width and height. When set, each load is
+    /// slid along the floor to bring it closer to the centre.
+    pub cog_envelope: Option<Coordinates>,
+    /// Seed for the random choices of the search.
+    pub seed: u64,
+    /// Replace the packings with a [`Strategy::GreedyBox`] stack when the
+    /// strategy needs more than one container but a single one holds the
+    /// stack. On by default.
+    pub greedy_fallback: bool,
+    /// Pack and report along the container's dimensions as given, its last
+    /// dimension being the height, rather than along its sides sorted
+    /// longest first. Allowed rotations and the balance envelope are read
+    /// along the same axes.
+    pub given_axes: bool,
+}
+
+impl Default for PackOptions {
+    fn default() -> Self {
+        PackOptions {
+            strategy: Strategy::default(),
+            legacy_positions: false,
+            time_limit_ms: None,
+            node_limit: None,
+            min_support: None,
+            cog_envelope: None,
+            seed: 0,
+            greedy_fallback: true,
+            given_axes: false,
+        }
+    }
+}
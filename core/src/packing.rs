@@ -0,0 +1,171 @@
+use crate::balance::centre_of_gravity;
+use crate::bounds::LowerBounds;
+use crate::geometry::{Coordinates, Dimensions};
+use crate::item::{Container, Item};
+use crate::options::Strategy;
+use crate::place::rotate;
+use crate::unpacked::Unpacked;
+
+/// A free region of a packing into which further items may be placed.
+#[derive(Clone, Debug)]
+pub struct Space {
+    pub dimensions: Dimensions,
+    /// The corner of the space closest to the container's origin.
+    pub position: Coordinates,
+}
+
+impl Space {
+    pub fn volume(&self) -> f64 {
+        self.dimensions.volume()
+    }
+}
+
+/// For each container axis, as packings are reported along, the index into
+/// the item's dimensions (as given) of the side lying along it.
+///
+/// Container axes are its sides sorted longest first, or its dimensions in
+/// the order given with [`given_axes`](crate::PackOptions::given_axes).
+pub type Rotation = [usize; 3];
+
+/// An item as it was placed inside a packing.
+///
+/// Positions and extents are along the container's axes, as for
+/// [`Rotation`].
+#[derive(Clone, Debug)]
+pub struct Placement {
+    /// Extents of the item as rotated into the container.
+    pub dimensions: Dimensions,
+    /// The corner of the item closest to the container's origin.
+    pub position: Coordinates,
+    pub rotation: Rotation,
+    pub weight: Option<f64>,
+    /// Weight of everything resting on the item, directly or not.
+    pub load: f64,
+    /// The item's [`Item::max_load`].
+    pub max_load: Option<f64>,
+    /// The item's [`Item::stackable`].
+    pub stackable: bool,
+    /// Share of the item's base resting on the container floor or on the
+    /// tops of other items.
+    pub support: f64,
+    /// The item's [`Item::id`].
+    pub id: Option<String>,
+    /// The item's [`Item::instance`].
+    pub instance: usize,
+    /// Extents of the free space the item went into, along the container's
+    /// sides sorted longest first, when placed with the free spaces of
+    /// [`Strategy::Spaces`], reported as its position with
+    /// [`legacy_positions`](crate::PackOptions::legacy_positions).
+    pub(crate) space: Option<Coordinates>,
+}
+
+impl Placement {
+    /// `item` turned by `rotation` with its corner closest to the origin at
+    /// `position`.
+    pub fn new(item: &Item, rotation: Rotation, position: Coordinates) -> Placement {
+        Placement {
+            dimensions: Dimensions::from_array(&rotate(&item.dimensions, &rotation)),
+            position,
+            rotation,
+            weight: item.weight,
+            load: 0.0,
+            max_load: item.max_load,
+            stackable: item.stackable,
+            support: 0.0,
+            id: item.id.clone(),
+            instance: item.instance,
+            space: None,
+        }
+    }
+}
+
+/// The contents of one opened container.
+#[derive(Clone, Debug)]
+pub struct Packing {
+    pub placements: Vec<Placement>,
+    /// Free room left, clear of the placements. Depending on the strategy
+    /// these may overlap each other.
+    pub spaces: Vec<Space>,
+    /// Weight of the items, see [`gross_weight`](Self::gross_weight).
+    pub weight: f64,
+    /// Volume inside the container.
+    pub volume: f64,
+    /// Outer size of the container.
+    pub outer_dimensions: Dimensions,
+    /// Weight of the empty container.
+    pub tare_weight: f64,
+    /// Index of the [`ContainerType`](crate::ContainerType) used, 0 when
+    /// packing into copies of a single container.
+    pub container_type: usize,
+    /// What a [`Carrier`](crate::Carrier) bills the container at, when
+    /// packing for one.
+    pub billable_weight: Option<f64>,
+}
+
+impl Packing {
+    /// An empty `container`.
+    pub fn new(container: &Container) -> Packing {
+        Packing {
+            placements: vec![],
+            spaces: vec![],
+            weight: 0.0,
+            volume: container.dimensions.volume(),
+            outer_dimensions: container.outer_dimensions.clone(),
+            tare_weight: container.tare_weight,
+            container_type: 0,
+            billable_weight: None,
+        }
+    }
+
+    /// The weighted mean of the centres of the placements, `None` if none of
+    /// them has a weight.
+    pub fn centre_of_gravity(&self) -> Option<Coordinates> {
+        centre_of_gravity(&self.placements)
+    }
+
+    /// Weight of the items together with the container.
+    pub fn gross_weight(&self) -> f64 {
+        self.weight + self.tare_weight
+    }
+
+    /// Volume of the container not taken up by any placement.
+    pub fn free_volume(&self) -> f64 {
+        self.volume - self.placements.iter().map(|p| p.dimensions.volume()).sum::<f64>()
+    }
+}
+
+/// Outcome of [`pack`](crate::pack).
+#[derive(Clone, Debug, Default)]
+pub struct PackResult {
+    pub packings: Vec<Packing>,
+    /// One message per item that could not be packed.
+    pub errors: Vec<String>,
+    /// The items that could not be packed, in the order of
+    /// [`errors`](Self::errors), with the reasons why.
+    pub unpacked: Vec<Unpacked>,
+    /// The strategy that produced the packings, which differs from the one
+    /// asked for when the greedy box took over or could not be used.
+    pub strategy: Strategy,
+    /// Bounds on the number of containers needed. They are only computed for
+    /// a single container, `None` with several container types.
+    pub lower_bounds: Option<LowerBounds>,
+    /// Whether no packing can use fewer containers, because every item was
+    /// packed and the packings meet [`LowerBounds::best`], so always false
+    /// without lower bounds. A
+    /// search that ran out of options without reaching the bound proves
+    /// nothing, as it only tries the positions the free space heuristic
+    /// offers.
+    pub proven_optimal: bool,
+    /// Total cost of the packings, when packing into
+    /// [`ContainerType`](crate::ContainerType)s, or what shipping them costs
+    /// when packing for a [`Carrier`](crate::Carrier).
+    pub cost: Option<f64>,
+}
+
+impl PackResult {
+    /// How far the number of packings is above the best lower bound, as a
+    /// fraction of it, `None` without lower bounds.
+    pub fn gap(&self) -> Option<f64> {
+        self.lower_bounds.map(|lower_bounds| lower_bounds.gap(self.packings.len()))
+    }
+}
@@ -0,0 +1,410 @@
+use crate::geometry::{cmp_coordinates, Coordinates, Dimensions};
+use crate::item::{Container, Item};
+use crate::layout::Layout;
+use crate::options::PackOptions;
+use crate::packing::{Placement, Rotation, Space};
+use crate::support::Stability;
+use std::cmp::Ordering;
+
+/// Orders candidate splits of a space by the `cmp_hwl` of each of their
+/// three sub-spaces in turn.
+pub fn cmp_dimensions_and_position(a: &[Space; 3], b: &[Space; 3]) -> Ordering {
+    let ordering = a[0].dimensions.cmp_hwl(&b[0].dimensions);
+    if ordering != Ordering::Equal {
+        return ordering;
+    }
+
+    let ordering = a[1].dimensions.cmp_hwl(&b[1].dimensions);
+    if ordering != Ordering::Equal {
+        return ordering;
+    }
+
+    a[2].dimensions.cmp_hwl(&b[2].dimensions)
+}
+
+struct RotationAndMargin {
+    rotation: Rotation,
+    sorted_margins: Coordinates,
+}
+
+/// Fits `item` into `space` using the rotation that leaves the smallest
+/// margins, or returns `None` if no rotation fits. The item is placed in the
+/// corner of the space at `space.position`.
+pub fn place(item: &Item, space: &Space) -> Option<Placement> {
+    let permutations = allowed_rotations(item);
+
+    let mut possible_rotations_and_margins: Vec<RotationAndMargin> = Vec::with_capacity(6);
+
+    for &rotation in permutations.iter() {
+        let extents = rotate(&item.dimensions, &rotation);
+        if extents[0] > space.dimensions[0]
+            || extents[1] > space.dimensions[1]
+            || extents[2] > space.dimensions[2]
+        {
+            continue;
+        }
+        let mut sorted_margins = [
+            space.dimensions[0] - extents[0],
+            space.dimensions[1] - extents[1],
+            space.dimensions[2] - extents[2],
+        ];
+        sorted_margins.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        possible_rotations_and_margins.push(RotationAndMargin {
+            rotation,
+            sorted_margins,
+        });
+    }
+
+    if possible_rotations_and_margins.is_empty() {
+        return None;
+    }
+
+    possible_rotations_and_margins
+        .sort_by(|a, b| cmp_coordinates(&a.sorted_margins, &b.sorted_margins));
+
+    let best = &possible_rotations_and_margins[0];
+    Some(Placement::new(item, best.rotation, space.position))
+}
+
+/// Fits `item` turned by `rotation` into the corner of `space`, if it fits.
+pub(crate) fn place_rotated(item: &Item, space: &Space, rotation: &Rotation) -> Option<Placement> {
+    let extents = rotate(&item.dimensions, rotation);
+    if !item.allows_rotation(rotation) || (0..3).any(|axis| extents[axis] > space.dimensions[axis]) {
+        return None;
+    }
+    Some(Placement::new(item, *rotation, space.position))
+}
+
+/// The six ways of turning `item` into the container's axes.
+pub fn rotations(item: &Item) -> [Rotation; 6] {
+    // indices of the item's height, width and length within its dimensions as given
+    let mut sorted: Rotation = [0, 1, 2];
+    sorted.sort_by(|&a, &b| item.dimensions[a].total_cmp(&item.dimensions[b]));
+    let [height, width, length] = sorted;
+    [
+        [width,  height, length],
+        [width,  length, height],
+        [height, width,  length],
+        [height, length, width],
+        [length, width,  height],
+        [length, height, width],
+    ]
+}
+
+/// The [`rotations`] of `item` it may be placed in, in the same order.
+pub fn allowed_rotations(item: &Item) -> Vec<Rotation> {
+    rotations(item).iter().copied().filter(|rotation| item.allows_rotation(rotation)).collect()
+}
+
+/// The extents along each container axis of `dimensions` turned by `rotation`.
+pub fn rotate(dimensions: &Dimensions, rotation: &Rotation) -> Coordinates {
+    [dimensions[rotation[0]], dimensions[rotation[1]], dimensions[rotation[2]]]
+}
+
+pub fn break_up_space(space: &Space, placement: &Placement) -> [Space; 3] {
+    let mut possible_spaces: [[Space; 3]; 6] = [
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    space.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    placement.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+        ],
+        // HEIGHT SPACE => LENGTH => WIDTH
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    space.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    space.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+        ],
+        // LENGTH SPACE => HEIGHT => WIDTH
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    space.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    space.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+        ],
+        // LENGTH SPACE => WIDTH  => HEIGHT
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    space.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    placement.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+        ],
+        // WIDTH SPACE  => LENGTH => HEIGHT
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    placement.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    placement.dimensions[0],
+                    placement.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+        ],
+        // WIDTH SPACE  => HEIGHT => LENGTH
+        [
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    space.dimensions[1] - placement.dimensions[1],
+                    space.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1] + placement.dimensions[1],
+                    space.position[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0],
+                    placement.dimensions[1],
+                    space.dimensions[2] - placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0],
+                    space.position[1],
+                    space.position[2] + placement.dimensions[2],
+                ],
+            },
+            Space {
+                dimensions: Dimensions::from_array(&[
+                    space.dimensions[0] - placement.dimensions[0],
+                    placement.dimensions[1],
+                    placement.dimensions[2],
+                ]),
+                position: [
+                    space.position[0] + placement.dimensions[0],
+                    space.position[1],
+                    space.position[2],
+                ],
+            },
+        ],
+    ];
+
+    // PICK biggest
+    possible_spaces.sort_by(cmp_dimensions_and_position);
+    let [_, _, _, _, _, biggest] = possible_spaces;
+    biggest
+}
+
+/// The free room of a container as disjoint spaces, each split in three by
+/// [`break_up_space`] when an item is placed into it.
+#[derive(Clone)]
+pub(crate) struct GuillotineSpaces {
+    spaces: Vec<Space>,
+    stability: Stability,
+}
+
+impl GuillotineSpaces {
+    /// Free room made up of `spaces`, which must not overlap.
+    pub(crate) fn from_spaces(spaces: Vec<Space>, container: &Container, options: &PackOptions) -> Self {
+        GuillotineSpaces { spaces, stability: Stability::new(container, options) }
+    }
+
+    /// [`Layout::place`] turning the item by `rotation` only.
+    pub(crate) fn place_exactly(&mut self, item: &Item, rotation: &Rotation, placements: &[Placement]) -> Option<Placement> {
+        self.place_with(placements, |space| place_rotated(item, space, rotation))
+    }
+
+    fn place_with<F: Fn(&Space) -> Option<Placement>>(&mut self, placements: &[Placement], place: F) -> Option<Placement> {
+        // remove volume size = 0 (not possible to pack)
+        self.spaces.retain(|space| space.dimensions.volume() > 0.0);
+        // try minimum space first
+        self.spaces.sort_by(|a, b| a.dimensions.cmp_hwl(&b.dimensions));
+        for (i, space) in self.spaces.iter().enumerate() {
+            // Try placing the item in this space,
+            // if it doesn't fit skip on the next space
+            if let Some(mut p) = place(space).filter(|p| self.stability.allows(placements, p)) {
+                let new_spaces = break_up_space(space, &p);
+                p.space = Some(space.dimensions.dimensions);
+                self.spaces.remove(i);
+                self.spaces.extend_from_slice(&new_spaces);
+                return Some(p);
+            }
+        }
+        None
+    }
+}
+
+impl Layout for GuillotineSpaces {
+    fn new(container: &Container, options: &PackOptions) -> Self {
+        let space = Space {
+            dimensions: container.dimensions.clone(),
+            position: [0.0, 0.0, 0.0],
+        };
+        GuillotineSpaces::from_spaces(vec![space], container, options)
+    }
+
+    fn place(&mut self, item: &Item, placements: &[Placement]) -> Option<Placement> {
+        self.place_preferring(item, None, placements)
+    }
+
+    /// Turns the item by `rotation` wherever that fits, in whichever space
+    /// comes first.
+    fn place_preferring(&mut self, item: &Item, rotation: Option<&Rotation>, placements: &[Placement]) -> Option<Placement> {
+        self.place_with(placements, |space| {
+            rotation.and_then(|rotation| place_rotated(item, space, rotation)).or_else(|| place(item, space))
+        })
+    }
+
+    fn spaces(&self, _placements: &[Placement]) -> Vec<Space> {
+        self.spaces.iter().filter(|space| space.volume() > 0.0).cloned().collect()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{self, PackError};
+use crate::geometry::{Coordinates, Dimensions};
+use crate::greedy::{check_container_is_bigger_than_greedy_box, item_greedy_box};
+use crate::item::{Container, Item, WeightLimit};
+use crate::pack;
+
+fn volume(a: &Coordinates) -> f64 {
+    a[0] * a[1] * a[2]
+}
+
+fn sum(a: &Coordinates) -> f64 {
+    a[0] + a[1] + a[2]
+}
+
+/// Sample standard deviation of the sides, low for cube-like boxes.
+fn std(a: &Coordinates) -> f64 {
+    let mean = sum(a) / 3.0;
+    let sum_sqr = a[0] * a[0] + a[1] * a[1] + a[2] * a[2];
+    ((sum_sqr - 3.0 * mean * mean) / 2.0).abs().sqrt()
+}
+
+fn sorted(mut a: Coordinates) -> Coordinates {
+    a.sort_by(|x, y| x.total_cmp(y));
+    a
+}
+
+fn descending(a: &Coordinates) -> Coordinates {
+    let [small, middle, large] = sorted(*a);
+    [large, middle, small]
+}
+
+/// The boxes made by putting `item` beside `container` along each axis, for
+/// three rotations of each. Sides come sorted ascending, without duplicates.
+fn extensions(container: &Coordinates, item: &Coordinates) -> Vec<Coordinates> {
+    let [c_length, c_width, c_height] = descending(container);
+    let [b_length, b_width, b_height] = descending(item);
+    let c_permutations = [[c_width, c_height, c_length], [c_length, c_width, c_height], [c_length, c_height, c_width]];
+    let b_permutations = [[b_width, b_height, b_length], [b_length, b_width, b_height], [b_length, b_height, b_width]];
+
+    let mut extensions: Vec<Coordinates> = Vec::with_capacity(27);
+    for c in c_permutations.iter() {
+        for b in b_permutations.iter() {
+            for axis in 0..3 {
+                let mut candidate = [c[0].max(b[0]), c[1].max(b[1]), c[2].max(b[2])];
+                candidate[axis] = c[axis] + b[axis];
+                let candidate = sorted(candidate);
+                if !extensions.contains(&candidate) {
+                    extensions.push(candidate);
+                }
+            }
+        }
+    }
+    extensions
+}
+
+fn cmp_size(a: &Coordinates, b: &Coordinates) -> std::cmp::Ordering {
+    volume(a).total_cmp(&volume(b)).then(sum(a).total_cmp(&sum(b)))
+}
+
+/// Grows a box item by item, collecting every box on the way that is big
+/// enough by volume. Each step continues from the most cube-like box.
+fn possible_containers(items: &[Coordinates], min_volume: f64) -> Vec<Coordinates> {
+    let mut possible: Vec<Coordinates> = Vec::new();
+    let mut container = items[0];
+    for item in &items[1..] {
+        let mut untried = extensions(&container, item);
+        untried.retain(|candidate| !possible.contains(candidate));
+        if untried.is_empty() {
+            break;
+        }
+        let mut by_size = untried.clone();
+        by_size.sort_by(cmp_size);
+        possible.extend(by_size.into_iter().filter(|candidate| volume(candidate) >= min_volume));
+
+        container = untried
+            .into_iter()
+            .min_by(|a, b| std(a).total_cmp(&std(b)).then(cmp_size(a, b)))
+            .expect("untried is not empty");
+    }
+    possible
+}
+
+/// Finds up to `max_count` of the smallest boxes that [`pack`] fills with
+/// all of `items` in one go, smallest volume first.
+///
+/// Candidates are built by putting the items, biggest first, beside each
+/// other along every axis. A box at least as big as the
+/// [greedy box](item_greedy_box) is replaced by the greedy box. If any box
+/// fits inside `limit_dimensions`, in any orientation, only those are
+/// returned; otherwise all of them are, so there is still a box to fall
+/// back to.
+pub fn find_smallest_containers(
+    items: &[Item],
+    max_count: usize,
+    limit_dimensions: Option<Coordinates>,
+) -> Result<Vec<Dimensions>, PackError> {
+    let unlimited = |dimensions: Coordinates| Container::new(dimensions, WeightLimit::Unlimited);
+    if let Some(item) = items.first() {
+        error::check_input(&unlimited(item.dimensions.dimensions), items)?;
+    }
+    if items.len() <= 1 {
+        return Ok(items.iter().map(|item| item.dimensions.clone()).collect());
+    }
+
+    let min_volume: f64 = items.iter().map(|item| volume(&item.dimensions.dimensions)).sum();
+    let mut sides: Vec<Coordinates> = items.iter().map(|item| sorted(item.dimensions.dimensions)).collect();
+    sides.sort_by(|a, b| b[0].total_cmp(&a[0]).then(b[1].total_cmp(&a[1])).then(b[2].total_cmp(&a[2])));
+
+    let mut candidates = possible_containers(&sides, min_volume);
+    candidates.sort_by(cmp_size);
+
+    let mut containers: Vec<Dimensions> = Vec::new();
+    for candidate in candidates {
+        if containers.len() == max_count {
+            break;
+        }
+        let container = unlimited(candidate);
+        let result = pack(&container, items)?;
+        if result.packings.len() == 1 && result.errors.is_empty() {
+            containers.push(if check_container_is_bigger_than_greedy_box(&container, items) {
+                item_greedy_box(items)
+            } else {
+                container.dimensions
+            });
+        }
+    }
+
+    if let Some(limit) = limit_dimensions {
+        let limit = sorted(limit);
+        let within: Vec<Dimensions> = containers
+            .iter()
+            .filter(|container| {
+                let sides = sorted(container.dimensions);
+                (0..3).all(|axis| sides[axis] <= limit[axis])
+            })
+            .cloned()
+            .collect();
+        if !within.is_empty() {
+            return Ok(within);
+        }
+    }
+    Ok(containers)
+}
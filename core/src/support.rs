@@ -0,0 +1,140 @@
+use crate::balance::balanced;
+use crate::geometry::Coordinates;
+use crate::item::Container;
+use crate::options::PackOptions;
+use crate::packing::Placement;
+
+/// Tolerance when matching the top of one placement to the bottom of another.
+const EPSILON: f64 = 1e-9;
+
+/// Indices of the placements `placement` rests on directly: those whose top
+/// is level with its bottom and whose footprint overlaps its own.
+pub(crate) fn supports(placements: &[Placement], placement: &Placement) -> Vec<usize> {
+    let bottom = placement.position[2];
+    placements
+        .iter()
+        .enumerate()
+        .filter(|(_, below)| {
+            (below.position[2] + below.dimensions[2] - bottom).abs() < EPSILON
+                && (0..2).all(|axis| {
+                    below.position[axis] < placement.position[axis] + placement.dimensions[axis]
+                        && placement.position[axis] < below.position[axis] + below.dimensions[axis]
+                })
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Indices of every placement `placement` rests on, directly or through
+/// others, each once.
+fn beneath(placements: &[Placement], placement: &Placement) -> Vec<usize> {
+    let mut seen = vec![false; placements.len()];
+    let mut stack = supports(placements, placement);
+    let mut all = Vec::new();
+    while let Some(i) = stack.pop() {
+        if seen[i] {
+            continue;
+        }
+        seen[i] = true;
+        all.push(i);
+        stack.extend(supports(placements, &placements[i]));
+    }
+    all
+}
+
+/// For each placement, the weight of everything resting on it, directly or
+/// not, and whether anything rests on it directly. The whole weight of an
+/// item counts against every item below it, however it is spread.
+fn loads(placements: &[Placement]) -> (Vec<f64>, Vec<bool>) {
+    let mut loads = vec![0.0; placements.len()];
+    let mut stacked = vec![false; placements.len()];
+    for placement in placements {
+        for i in supports(placements, placement) {
+            stacked[i] = true;
+        }
+        let weight = placement.weight.unwrap_or(0.0);
+        if weight != 0.0 {
+            for i in beneath(placements, placement) {
+                loads[i] += weight;
+            }
+        }
+    }
+    (loads, stacked)
+}
+
+fn unconstrained(placement: &Placement) -> bool {
+    placement.stackable && placement.max_load.is_none()
+}
+
+/// Share of the base of `placement` resting on the container floor or on the
+/// tops of `placements`.
+pub(crate) fn support_ratio(placements: &[Placement], placement: &Placement) -> f64 {
+    if placement.position[2].abs() < EPSILON {
+        return 1.0;
+    }
+    let overlap = |below: &Placement, axis: usize| {
+        (below.position[axis] + below.dimensions[axis]).min(placement.position[axis] + placement.dimensions[axis])
+            - below.position[axis].max(placement.position[axis])
+    };
+    let supported: f64 = supports(placements, placement)
+        .into_iter()
+        .map(|i| overlap(&placements[i], 0) * overlap(&placements[i], 1))
+        .sum();
+    supported / (placement.dimensions[0] * placement.dimensions[1])
+}
+
+/// Whether `candidate` may go next to `placements` without anything resting
+/// on an item that is not stackable or pushing an item over its `max_load`.
+/// Checks both ways, as some strategies fill room below items already placed.
+fn bears(placements: &[Placement], candidate: &Placement) -> bool {
+    if unconstrained(candidate) && placements.iter().all(unconstrained) {
+        return true;
+    }
+    let mut all = placements.to_vec();
+    all.push(candidate.clone());
+    let (loads, stacked) = loads(&all);
+    all.iter().enumerate().all(|(i, placement)| {
+        (placement.stackable || !stacked[i]) && placement.max_load.is_none_or(|max_load| loads[i] <= max_load + EPSILON)
+    })
+}
+
+/// Sets the [`load`](Placement::load) and [`support`](Placement::support) of
+/// each of `placements`.
+pub(crate) fn record_support(placements: &mut [Placement]) {
+    let (loads, _) = loads(placements);
+    let supports: Vec<f64> = placements.iter().map(|placement| support_ratio(placements, placement)).collect();
+    for ((placement, load), support) in placements.iter_mut().zip(loads).zip(supports) {
+        placement.load = load;
+        placement.support = support;
+    }
+}
+
+/// What a placement must satisfy besides fitting into free room.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Stability {
+    bounds: Coordinates,
+    min_support: Option<f64>,
+    cog_envelope: Option<Coordinates>,
+}
+
+impl Stability {
+    pub(crate) fn new(container: &Container, options: &PackOptions) -> Self {
+        Stability {
+            bounds: container.dimensions.dimensions,
+            min_support: options.min_support,
+            cog_envelope: options.cog_envelope,
+        }
+    }
+
+    /// Whether `candidate` is supported well enough, overloads nothing and
+    /// keeps the load balanced next to `placements`.
+    pub(crate) fn allows(&self, placements: &[Placement], candidate: &Placement) -> bool {
+        self.min_support.is_none_or(|min_support| support_ratio(placements, candidate) + EPSILON >= min_support)
+            && bears(placements, candidate)
+            && self.cog_envelope.is_none_or(|envelope| {
+                let mut all = placements.to_vec();
+                all.push(candidate.clone());
+                balanced(&all, &self.bounds, &envelope)
+            })
+    }
+}
@@ -0,0 +1,97 @@
+use crate::frame::Frame;
+use crate::item::{Container, Item};
+use crate::options::PackOptions;
+use crate::place::{allowed_rotations, rotate};
+use std::fmt;
+
+/// Why an item was left out of every packing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// The item alone weighs more than the container may carry.
+    TooHeavy,
+    /// The item does not fit into the container whichever way it is turned.
+    TooLarge,
+    /// The item only fits turned in a way its allowed rotations rule out.
+    ViolatesOrientation,
+    /// Some container type would hold the item, but none is left in stock.
+    ExceedsStock,
+    /// The item fits, but not in a way the stacking, support or balance
+    /// limits allow.
+    Unstable,
+}
+
+impl Reason {
+    /// A snake case name for the reason, e.g. `"too_heavy"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Reason::TooHeavy => "too_heavy",
+            Reason::TooLarge => "too_large",
+            Reason::ViolatesOrientation => "violates_orientation",
+            Reason::ExceedsStock => "exceeds_stock",
+            Reason::Unstable => "unstable",
+        }
+    }
+}
+
+/// An item left out of every packing, and why.
+#[derive(Clone, Debug)]
+pub struct Unpacked {
+    pub item: Item,
+    pub reason: Reason,
+    /// The container side or weight limit the item exceeds, if any.
+    pub limit: Option<f64>,
+}
+
+impl Unpacked {
+    pub(crate) fn too_heavy(container: &Container, item: &Item) -> Unpacked {
+        Unpacked { item: item.clone(), reason: Reason::TooHeavy, limit: container.payload_limit() }
+    }
+
+    /// Works out why `item` cannot go into an empty `container`, both along
+    /// the container's packing axes.
+    pub(crate) fn classify(container: &Container, item: &Item) -> Unpacked {
+        if !container.allows(item.weight.unwrap_or(0.0)) {
+            return Unpacked::too_heavy(container, item);
+        }
+        let exceeded = |extents: [f64; 3], bounds: [f64; 3]| (0..3).find(|&axis| extents[axis] > bounds[axis]).map(|axis| bounds[axis]);
+
+        // longest side against longest side tells whether any rotation fits
+        let sorted = [item.dimensions.length, item.dimensions.width, item.dimensions.height];
+        let sorted_bounds = [container.dimensions.length, container.dimensions.width, container.dimensions.height];
+        if let Some(limit) = exceeded(sorted, sorted_bounds) {
+            return Unpacked { item: item.clone(), reason: Reason::TooLarge, limit: Some(limit) };
+        }
+        let bounds = container.dimensions.dimensions;
+        let limits: Vec<Option<f64>> =
+            allowed_rotations(item).iter().map(|rotation| exceeded(rotate(&item.dimensions, rotation), bounds)).collect();
+        if limits.iter().all(Option::is_some) {
+            let limit = limits.first().copied().flatten();
+            return Unpacked { item: item.clone(), reason: Reason::ViolatesOrientation, limit };
+        }
+        Unpacked { item: item.clone(), reason: Reason::Unstable, limit: None }
+    }
+
+    /// Works out why `item` cannot go into any of `containers`, judged by
+    /// the biggest of them along the axes `options` pack along.
+    pub(crate) fn classify_biggest<'a>(containers: impl Iterator<Item = &'a Container>, item: &Item, options: &PackOptions) -> Unpacked {
+        match containers.max_by(|a, b| a.dimensions.volume().total_cmp(&b.dimensions.volume())) {
+            Some(container) => {
+                let frame = Frame::new(container, options);
+                Unpacked { item: item.clone(), ..Unpacked::classify(&frame.container(container), &frame.item(item)) }
+            }
+            None => Unpacked { item: item.clone(), reason: Reason::TooLarge, limit: None },
+        }
+    }
+}
+
+impl fmt::Display for Unpacked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason {
+            Reason::TooHeavy => write!(f, "Item: {} is too heavy for container", self.item),
+            Reason::ExceedsStock => write!(f, "Item: {} cannot be placed: no container left in stock", self.item),
+            Reason::TooLarge | Reason::ViolatesOrientation | Reason::Unstable => {
+                write!(f, "Item: {} cannot be placed in container", self.item)
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::frame::Frame;
+use crate::item::{Container, Item};
+use crate::options::PackOptions;
+use crate::packing::{Packing, Placement};
+use std::fmt;
+
+/// How far sides, positions and weights may be off, to allow for rounding.
+const TOLERANCE: f64 = 1e-6;
+
+/// Something physically wrong with the packings checked by [`validate`].
+///
+/// Packings and placements are given by their index, items by their index
+/// in the items handed to `validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// A placement reaches outside the container.
+    OutOfBounds { packing: usize, placement: usize },
+    /// Two placements of the same packing take up some of the same room.
+    Overlap { packing: usize, placements: (usize, usize) },
+    /// A placement is none of the items, or has their id but not their
+    /// sides or weight.
+    UnknownPlacement { packing: usize, placement: usize },
+    /// An item was placed more than once.
+    Duplicate { packing: usize, placement: usize, item: usize },
+    /// An item was not placed at all.
+    Missing { item: usize },
+    /// A packing's weight is not what its placements weigh.
+    WeightMismatch { packing: usize, reported: f64, actual: f64 },
+    /// A packing, with its container, weighs more than the container may
+    /// carry. `limit` is what the items may weigh.
+    Overweight { packing: usize, weight: f64, limit: f64 },
+}
+
+impl Violation {
+    /// A snake case name for the violation, e.g. `"out_of_bounds"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Violation::OutOfBounds { .. } => "out_of_bounds",
+            Violation::Overlap { .. } => "overlap",
+            Violation::UnknownPlacement { .. } => "unknown_placement",
+            Violation::Duplicate { .. } => "duplicate",
+            Violation::Missing { .. } => "missing",
+            Violation::WeightMismatch { .. } => "weight_mismatch",
+            Violation::Overweight { .. } => "overweight",
+        }
+    }
+
+    /// Index of the packing at fault, `None` for a missing item.
+    pub fn packing(&self) -> Option<usize> {
+        match *self {
+            Violation::OutOfBounds { packing, .. }
+            | Violation::Overlap { packing, .. }
+            | Violation::UnknownPlacement { packing, .. }
+            | Violation::Duplicate { packing, .. }
+            | Violation::WeightMismatch { packing, .. }
+            | Violation::Overweight { packing, .. } => Some(packing),
+            Violation::Missing { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::OutOfBounds { packing, placement } => {
+                write!(f, "packing {}: placement {} is outside the container", packing, placement)
+            }
+            Violation::Overlap { packing, placements: (a, b) } => {
+                write!(f, "packing {}: placements {} and {} overlap", packing, a, b)
+            }
+            Violation::UnknownPlacement { packing, placement } => {
+                write!(f, "packing {}: placement {} is none of the items", packing, placement)
+            }
+            Violation::Duplicate { packing, placement, item } => {
+                write!(f, "packing {}: placement {} places item {} again", packing, placement, item)
+            }
+            Violation::Missing { item } => write!(f, "item {} is not placed", item),
+            Violation::WeightMismatch { packing, reported, actual } => {
+                write!(f, "packing {}: weight {} should be {}", packing, reported, actual)
+            }
+            Violation::Overweight { packing, weight, limit } => {
+                write!(f, "packing {}: weight {} is over the limit of {}", packing, weight, limit)
+            }
+        }
+    }
+}
+
+/// Checks that `packings` of `items` in copies of `container` could be
+/// packed for real, independently of how they were made, and returns what
+/// is wrong with them, nothing if they are sound.
+///
+/// Every placement must lie inside the container and overlap no other
+/// placement in its packing, and every item must be placed exactly once,
+/// turned so that its sides are a permutation of the item's. Items with an
+/// [`id`](Item::id) are told apart by it and their
+/// [`instance`](Item::instance), others by their sides and weight. Each
+/// packing's weight must be that of its placements and, together with the
+/// container's tare, within the weight limit.
+///
+/// Placements are taken to lie along the container's sides sorted longest
+/// first, as [`pack`](crate::pack) reports them. Leave items reported as
+/// unpacked out of `items`, and don't check packings made with
+/// [`legacy_positions`](PackOptions::legacy_positions), whose positions are
+/// not the placements' corners.
+pub fn validate(container: &Container, items: &[Item], packings: &[Packing]) -> Vec<Violation> {
+    validate_with_options(container, items, packings, &PackOptions::default())
+}
+
+/// [`validate`] for packings made with `options`, whose
+/// [`given_axes`](PackOptions::given_axes) decides which way placements lie
+/// in the container.
+pub fn validate_with_options(container: &Container, items: &[Item], packings: &[Packing], options: &PackOptions) -> Vec<Violation> {
+    let container = &Frame::new(container, options).reported(container);
+    let mut violations = Vec::new();
+    let mut placed = vec![false; items.len()];
+    for (p, packing) in packings.iter().enumerate() {
+        for (i, placement) in packing.placements.iter().enumerate() {
+            if !is_inside(container, placement) {
+                violations.push(Violation::OutOfBounds { packing: p, placement: i });
+            }
+            for (j, other) in packing.placements.iter().enumerate().skip(i + 1) {
+                if overlap(placement, other) {
+                    violations.push(Violation::Overlap { packing: p, placements: (i, j) });
+                }
+            }
+            match find_item(items, &placed, placement) {
+                Some(Ok(item)) => placed[item] = true,
+                Some(Err(item)) => violations.push(Violation::Duplicate { packing: p, placement: i, item }),
+                None => violations.push(Violation::UnknownPlacement { packing: p, placement: i }),
+            }
+        }
+
+        let actual: f64 = packing.placements.iter().filter_map(|placement| placement.weight).sum();
+        if (packing.weight - actual).abs() > TOLERANCE {
+            violations.push(Violation::WeightMismatch { packing: p, reported: packing.weight, actual });
+        }
+        if let Some(limit) = container.payload_limit().filter(|_| !container.allows(actual - TOLERANCE)) {
+            violations.push(Violation::Overweight { packing: p, weight: actual, limit });
+        }
+    }
+    for item in placed.iter().enumerate().filter(|(_, &placed)| !placed).map(|(item, _)| item) {
+        violations.push(Violation::Missing { item });
+    }
+    violations
+}
+
+fn is_inside(container: &Container, placement: &Placement) -> bool {
+    let bounds = container.dimensions.dimensions;
+    let extents = placement.dimensions.dimensions;
+    (0..3).all(|axis| {
+        placement.position[axis] >= -TOLERANCE && placement.position[axis] + extents[axis] <= bounds[axis] + TOLERANCE
+    })
+}
+
+fn overlap(a: &Placement, b: &Placement) -> bool {
+    let (a_extents, b_extents) = (a.dimensions.dimensions, b.dimensions.dimensions);
+    (0..3).all(|axis| {
+        let start = a.position[axis].max(b.position[axis]);
+        let end = (a.position[axis] + a_extents[axis]).min(b.position[axis] + b_extents[axis]);
+        end - start > TOLERANCE
+    })
+}
+
+/// Whether `placement` has the sides, in any order, and the weight of `item`.
+fn matches(item: &Item, placement: &Placement) -> bool {
+    let close = |a: f64, b: f64| (a - b).abs() <= TOLERANCE;
+    let (sides, placed) = (&item.dimensions, &placement.dimensions);
+    let same_weight = match (item.weight, placement.weight) {
+        (Some(a), Some(b)) => close(a, b),
+        (a, b) => a.is_none() && b.is_none(),
+    };
+    close(sides.length, placed.length) && close(sides.width, placed.width) && close(sides.height, placed.height) && same_weight
+}
+
+/// The item `placement` places, `Ok` if it was not placed yet and `Err`
+/// if it was, `None` if it is none of the items.
+fn find_item(items: &[Item], placed: &[bool], placement: &Placement) -> Option<Result<usize, usize>> {
+    if placement.id.is_some() {
+        let item = items.iter().position(|item| item.id == placement.id && item.instance == placement.instance)?;
+        if !matches(&items[item], placement) {
+            return None;
+        }
+        return Some(if placed[item] { Err(item) } else { Ok(item) });
+    }
+    let mut candidates = items.iter().enumerate().filter(|(_, item)| item.id.is_none() && matches(item, placement));
+    let first = candidates.clone().next().map(|(item, _)| item)?;
+    Some(candidates.find(|&(item, _)| !placed[item]).map(|(item, _)| item).ok_or(first))
+}
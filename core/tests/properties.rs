@@ -0,0 +1,252 @@
+use easy_box_packer_core::{
+    break_up_space, cmp_dimensions_and_position, pack_container_types, pack_for_carrier, pack_with_options, place, rotate, rotations,
+    validate, validate_with_options, Carrier, Container, Dimensions, Item, Merit, PackOptions, Space, Strategy as PackStrategy,
+    WeightLimit,
+};
+use proptest::prelude::*;
+use std::cmp::Ordering;
+
+const STRATEGIES: [PackStrategy; 7] = [
+    PackStrategy::Spaces,
+    PackStrategy::ExtremePoints(Merit::ResidualSpace),
+    PackStrategy::ExtremePoints(Merit::FreeVolume),
+    PackStrategy::MaximalSpaces,
+    PackStrategy::Layers,
+    PackStrategy::Exact,
+    PackStrategy::GreedyBox,
+];
+
+fn side() -> impl Strategy<Value = f64> {
+    // quarter units, so that sides add up exactly as often as in real orders
+    (1u32..=48).prop_map(|quarters| quarters as f64 / 4.0)
+}
+
+fn coordinates() -> impl Strategy<Value = [f64; 3]> {
+    [side(), side(), side()]
+}
+
+fn container() -> impl Strategy<Value = Container> {
+    let limit = prop_oneof![Just(WeightLimit::Unlimited), (10u32..200).prop_map(|limit| WeightLimit::Bounded(limit as f64))];
+    ((4u32..=20, 4u32..=20, 4u32..=20), limit)
+        .prop_map(|((l, w, h), limit)| Container::new([l as f64, w as f64, h as f64], limit))
+}
+
+fn item() -> impl Strategy<Value = Item> {
+    let weight = prop::option::of((0u32..=40).prop_map(|weight| weight as f64 / 2.0));
+    (coordinates(), weight, 0u8..4).prop_map(|(dimensions, weight, constraint)| {
+        let item = Item::new(dimensions, weight);
+        match constraint {
+            0 => item.keep_upright(),
+            1 => item.with_allowed_rotations(vec![[0, 1, 2]]),
+            _ => item,
+        }
+    })
+}
+
+fn items() -> impl Strategy<Value = Vec<Item>> {
+    prop::collection::vec(item(), 0..20).prop_map(|items| {
+        // ids keep identical items apart when matching up the unpacked ones
+        items.into_iter().enumerate().map(|(i, item)| item.with_id(i.to_string())).collect()
+    })
+}
+
+/// [`items`], some of which bear only so much or nothing at all on top.
+fn loaded_items() -> impl Strategy<Value = Vec<Item>> {
+    let load = (prop::option::of((0u32..=20).prop_map(|load| load as f64)), any::<bool>());
+    (items(), prop::collection::vec(load, 20)).prop_map(|(items, loads)| {
+        items
+            .into_iter()
+            .zip(loads)
+            .map(|(item, (max_load, stackable))| {
+                let item = Item { max_load, ..item };
+                if stackable { item } else { item.not_stackable() }
+            })
+            .collect()
+    })
+}
+
+fn options(strategy: PackStrategy, min_support: Option<f64>, given_axes: bool) -> PackOptions {
+    PackOptions { strategy, node_limit: Some(2_000), min_support, given_axes, ..PackOptions::default() }
+}
+
+/// Packs with every strategy and checks the packings are sound, keep every
+/// item and its weight, turn items only as they allow and report free
+/// spaces inside the container and clear of the items.
+fn check_every_strategy(container: &Container, items: &[Item], min_support: Option<f64>, given_axes: bool) -> Result<(), TestCaseError> {
+    // the container's sides as packings are reported along
+    let bounds = if given_axes { container.dimensions.dimensions } else { descending(container.dimensions.dimensions) };
+    for &strategy in STRATEGIES.iter() {
+        let options = options(strategy, min_support, given_axes);
+        let result = pack_with_options(container, items, &options).unwrap();
+        let packed: Vec<Item> = items
+            .iter()
+            .filter(|item| !result.unpacked.iter().any(|unpacked| unpacked.item.id == item.id))
+            .cloned()
+            .collect();
+        let violations = validate_with_options(container, &packed, &result.packings, &options);
+        prop_assert!(violations.is_empty(), "{:?}: {:?}", strategy, violations);
+
+        let placed: usize = result.packings.iter().map(|packing| packing.placements.len()).sum();
+        prop_assert_eq!(placed + result.unpacked.len(), items.len(), "{:?}", strategy);
+        prop_assert_eq!(result.errors.len(), result.unpacked.len());
+        prop_assert!(result.packings.iter().all(|packing| !packing.placements.is_empty()), "{:?}", strategy);
+
+        let weight = |items: &mut dyn Iterator<Item = &Item>| items.filter_map(|item| item.weight).sum::<f64>();
+        let packed_weight: f64 = result.packings.iter().map(|packing| packing.weight).sum();
+        let unpacked_weight = weight(&mut result.unpacked.iter().map(|unpacked| &unpacked.item));
+        prop_assert!((packed_weight + unpacked_weight - weight(&mut items.iter())).abs() < 1e-6, "{:?}", strategy);
+
+        for placement in result.packings.iter().flat_map(|packing| &packing.placements) {
+            let item = items.iter().find(|item| item.id == placement.id).unwrap();
+            prop_assert!(item.allows_rotation(&placement.rotation), "{:?}: {:?}", strategy, placement);
+            if let Some(min_support) = min_support {
+                prop_assert!(placement.support + 1e-6 >= min_support, "{:?}: {:?}", strategy, placement);
+            }
+        }
+        for packing in &result.packings {
+            for space in &packing.spaces {
+                let inside = (0..3).all(|axis| space.position[axis] + space.dimensions[axis] <= bounds[axis] + 1e-6);
+                prop_assert!(inside, "{:?}: {:?}", strategy, space);
+                for placement in &packing.placements {
+                    let taken = Space { dimensions: placement.dimensions.clone(), position: placement.position };
+                    prop_assert!(disjoint(space, &taken), "{:?}: {:?} overlaps {:?}", strategy, space, placement);
+                }
+            }
+        }
+        if result.unpacked.is_empty() && strategy != PackStrategy::GreedyBox {
+            prop_assert!(result.packings.len() >= result.lower_bounds.unwrap().best(), "{:?}", strategy);
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn packings_are_valid(container in container(), items in items(), given_axes in any::<bool>()) {
+        check_every_strategy(&container, &items, None, given_axes)?;
+    }
+
+    #[test]
+    fn supported_packings_are_valid(container in container(), items in items(), min_support in 0.5f64..=1.0, given_axes in any::<bool>()) {
+        check_every_strategy(&container, &items, Some(min_support), given_axes)?;
+    }
+
+    #[test]
+    fn constrained_packings_place_or_report_each_item_once(
+        container in container(),
+        items in loaded_items(),
+        envelope in [0u32..=2, 0u32..=2, 0u32..=4],
+    ) {
+        let cog_envelope = Some([envelope[0] as f64, envelope[1] as f64, envelope[2] as f64]);
+        for &strategy in STRATEGIES.iter() {
+            let result = pack_with_options(&container, &items, &PackOptions { cog_envelope, ..options(strategy, None, false) }).unwrap();
+            let placed: Vec<&Option<String>> = result.packings.iter().flat_map(|packing| &packing.placements).map(|placement| &placement.id).collect();
+            for unpacked in &result.unpacked {
+                prop_assert!(!placed.contains(&&unpacked.item.id), "{:?}: {:?} is placed and unpacked", strategy, unpacked.item.id);
+            }
+            prop_assert_eq!(placed.len() + result.unpacked.len(), items.len(), "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn greedy_fallback_packings_are_valid(container in container(), items in items()) {
+        let result = pack_with_options(&container, &items, &PackOptions::default()).unwrap();
+        let without = pack_with_options(&container, &items, &PackOptions { greedy_fallback: false, ..PackOptions::default() }).unwrap();
+        prop_assert!(result.packings.len() <= without.packings.len().max(1));
+        if result.strategy == PackStrategy::GreedyBox {
+            prop_assert_eq!(result.packings.len(), 1);
+            prop_assert!(validate(&container, &items, &result.packings).is_empty());
+        }
+    }
+
+    #[test]
+    fn invalid_items_are_turned_away_without_containers(
+        items in items(),
+        side in prop_oneof![Just(0.0), Just(-1.0), Just(f64::NAN)],
+        at in any::<prop::sample::Index>(),
+    ) {
+        let mut items = items;
+        let i = at.index(items.len() + 1);
+        items.insert(i, Item::new([1.0, side, 1.0], None));
+        let error = pack_container_types(&[], &items, &PackOptions::default()).unwrap_err();
+        prop_assert_eq!(error.item_index(), Some(i));
+        let error = pack_for_carrier(&[], &items, &Carrier::new(5000.0), &PackOptions::default()).unwrap_err();
+        prop_assert_eq!(error.item_index(), Some(i));
+    }
+}
+
+fn space() -> impl Strategy<Value = Space> {
+    (coordinates(), coordinates()).prop_map(|(dimensions, position)| Space { dimensions: Dimensions::from_array(&dimensions), position })
+}
+
+fn descending(mut sides: [f64; 3]) -> [f64; 3] {
+    sides.sort_by(|a, b| b.total_cmp(a));
+    sides
+}
+
+fn sorted(mut sides: [f64; 3]) -> [f64; 3] {
+    sides.sort_by(|a, b| a.total_cmp(b));
+    sides
+}
+
+fn volume(dimensions: &[f64; 3]) -> f64 {
+    dimensions[0] * dimensions[1] * dimensions[2]
+}
+
+fn disjoint(a: &Space, b: &Space) -> bool {
+    (0..3).any(|axis| {
+        a.position[axis] + a.dimensions[axis] <= b.position[axis] || b.position[axis] + b.dimensions[axis] <= a.position[axis]
+    })
+}
+
+proptest! {
+    #[test]
+    fn place_fits_the_item_into_the_corner(item in item(), space in space()) {
+        let fits = |rotation: &[usize; 3]| {
+            let extents = rotate(&item.dimensions, rotation);
+            item.allows_rotation(rotation) && (0..3).all(|axis| extents[axis] <= space.dimensions[axis])
+        };
+        let any_fits = rotations(&item).iter().any(&fits);
+        match place(&item, &space) {
+            Some(placement) => {
+                prop_assert!(fits(&placement.rotation));
+                prop_assert_eq!(placement.position, space.position);
+                prop_assert_eq!(sorted(placement.dimensions.dimensions), sorted(item.dimensions.dimensions));
+            }
+            None => prop_assert!(!any_fits),
+        }
+    }
+
+    #[test]
+    fn break_up_space_splits_off_disjoint_spaces(item in item(), space in space()) {
+        if let Some(placement) = place(&item, &space) {
+            let spaces = break_up_space(&space, &placement);
+            let placed = Space { dimensions: placement.dimensions.clone(), position: placement.position };
+            for (i, part) in spaces.iter().enumerate() {
+                for axis in 0..3 {
+                    prop_assert!(part.dimensions[axis] >= 0.0);
+                    prop_assert!(part.position[axis] >= space.position[axis]);
+                    prop_assert!(part.position[axis] + part.dimensions[axis] <= space.position[axis] + space.dimensions[axis]);
+                }
+                prop_assert!(part.volume() == 0.0 || disjoint(part, &placed));
+                for other in &spaces[i + 1..] {
+                    prop_assert!(part.volume() == 0.0 || other.volume() == 0.0 || disjoint(part, other));
+                }
+            }
+            let total: f64 = spaces.iter().map(Space::volume).sum::<f64>() + placement.dimensions.volume();
+            prop_assert!((total - volume(&space.dimensions.dimensions)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn cmp_dimensions_and_position_is_an_order(a in [space(), space(), space()], b in [space(), space(), space()]) {
+        prop_assert_eq!(cmp_dimensions_and_position(&a, &a), Ordering::Equal);
+        prop_assert_eq!(cmp_dimensions_and_position(&a, &b), cmp_dimensions_and_position(&b, &a).reverse());
+        let first = a[0].dimensions.cmp_hwl(&b[0].dimensions);
+        if first != Ordering::Equal {
+            prop_assert_eq!(cmp_dimensions_and_position(&a, &b), first);
+        }
+    }
+}
@@ -5,120 +5,172 @@ module RustBoxPacker
 end
 
 module EasyBoxPacker
-  class << self
-    def pack(container:, items:)
-      RustPacker.pack(container, items)
+  # Raised when a container or item hash cannot be packed as given.
+  # +item_index+ is the index of the offending item, nil for the container.
+  class InvalidInput < ArgumentError
+    attr_reader :item_index
+
+    def initialize(message = nil, item_index = nil)
+      super(message)
+      @item_index = item_index
     end
+  end
 
-    def find_smallest_container_with_limits(items:, limit_dimensions:)
-      possible = find_smallest_containers(items: items, max_count: 5)
-      # puts possible.inspect
-      l = limit_dimensions.sort
-      possible.each do |p|
-        return p if (p[0] <= l[0]) && (p[1] <= l[1]) && (p[2] <= l[2])
-      end
-      # In case there is not match, just use the smallest one...
-      possible[0];
+  class << self
+    # Items are hashes of +:dimensions+ (length, width and height) and an
+    # optional +:weight+. An +:id+ (or +:sku+) is echoed, together with the
+    # +:instance+ index of each of +:quantity+ copies, from 1 to 10_000, in
+    # every placement and error of the item. Items may also restrict how they are turned:
+    # +allowed_rotations+:: rotations the item may be placed in, each listing
+    #                       for the box's length, width and height the index
+    #                       of the item's dimension lying along it, as
+    #                       reported in each placement's +:rotation+
+    # +keep_upright+::      keep the last of the item's +:dimensions+ along
+    #                       the box's height
+    # +max_load+::          most weight that may rest on the item, directly
+    #                       or on top of other items; each placement reports
+    #                       its +:load+
+    # +stackable+::         +false+ keeps anything from being put on the item
+    #
+    # Options:
+    # +strategy+::         +:spaces+ (default) splits free space around every
+    #                      item, +:extreme_points+ places items on the corners
+    #                      projected from the items already placed,
+    #                      +:maximal_spaces+ keeps overlapping maximal free
+    #                      boxes, +:layers+ builds whole layers of identical
+    #                      items before packing the rest as +:spaces+,
+    #                      +:exact+ searches item orders and rotations for
+    #                      the fewest boxes, for small orders, and
+    #                      +:greedy_box+ stacks every item flat in one box,
+    #                      falling back to +:spaces+ if the stack does not fit
+    # +greedy_fallback+::  +false+ keeps the strategy's packings when they
+    #                      need several boxes but one box holds the items
+    #                      stacked; by default that stack is returned
+    # +merit+::            how +:extreme_points+ picks a corner,
+    #                      +:residual_space+ (default) or +:free_volume+
+    # +legacy_positions+:: report each placement's position as the size of the
+    #                      free space it went into, as releases up to 0.0.14
+    #                      did; support, loads and balance still go by the
+    #                      real positions. Only +:spaces+ and +:exact+ place
+    #                      every item into such a space, so other strategies
+    #                      and +given_axes+ raise +InvalidInput+, and no
+    #                      +greedy_fallback+ stack is tried
+    # +given_axes+::       pack along the box's +:dimensions+ in the order
+    #                      given, the last being its height, instead of
+    #                      along its sides sorted longest first
+    # +time_limit_ms+::    afterwards spend up to this long looking for a
+    #                      better item order and rotations, placed as the
+    #                      strategy places them; +:layers+ is searched with
+    #                      free spaces and reported as +:spaces+ if that
+    #                      does better; with +:exact+ the longest its
+    #                      search may take
+    # +node_limit+::       most steps the +:exact+ search may take, 100_000
+    #                      by default
+    # +min_support+::      share of an item's base, from 0 to 1, that must
+    #                      rest on the box floor or on other items; each
+    #                      placement reports its +:support+
+    # +cog_envelope+::     how far each box's centre of gravity may lie from
+    #                      the box's centre along its length, width and
+    #                      height; each load is slid along the floor to
+    #                      centre it. Every packing reports its
+    #                      +:centre_of_gravity+ (nil if nothing has a weight)
+    # +seed+::             seed for that search, to make runs repeatable
+    #
+    # A container is a hash of +:dimensions+ and an optional +:weight_limit+.
+    # Items go into its +:inner_dimensions+ when given, the +:dimensions+
+    # then being its outer size, no smaller along any axis. An optional +:tare_weight+, the weight of
+    # the empty box, counts against the weight limit. Every packing reports
+    # the +:gross_weight+, items and box together, and the
+    # +:outer_dimensions+ of its box. Placement and free space positions and
+    # extents, allowed rotations and the balance envelope run along the
+    # box's length, width and height: its sides sorted longest first, or its
+    # +:dimensions+ in the order given with +given_axes+. Items stand on the
+    # floor across the height, and support, loads and balance go by it.
+    #
+    # Each item that could not be packed is listed in +:unpacked+ with its
+    # +:id+, +:instance+, +:dimensions+ and +:weight+, a +:reason+ (one of
+    # +:too_heavy+, +:too_large+, +:violates_orientation+, +:exceeds_stock+
+    # and +:unstable+) and the +:limit+, the box side or weight it exceeds.
+    #
+    # The result's +:strategy+ is the one the packings were made with,
+    # +:greedy_box+ when the stack was chosen.
+    #
+    # Besides +:packings+ and +:errors+ the result holds +:lower_bounds+ on
+    # the number of containers needed (+:volume+, +:l1+, +:l2+, +:weight+ and
+    # the tightest, +:best+) and +:gap+, how far the packings are above +:best+
+    # as a fraction of it. +:proven_optimal+ is true when every item was
+    # packed and the packings meet that bound.
+    #
+    # Instead of a single +container+, +containers+ may list the box types
+    # in stock, each a container with a +:cost+ and optional +:stock+. The packing then
+    # looks for the lowest total +:cost+, and each packing reports the index
+    # of its +:container_type+. The lower bounds only cover a single box type,
+    # so +:lower_bounds+ and +:gap+ are nil. The +:layers+, +:exact+ and
+    # +:greedy_box+ strategies fill each box with free spaces instead, as the
+    # result's +:strategy+ says, and +time_limit_ms+ is turned away.
+    #
+    # Given a +carrier+ as well, +containers+ is a catalog of boxes to ship
+    # in and needs no +:cost+. Carriers bill each box by the greater of its
+    # weight and its volume over the carrier's +:dim_divisor+, and optional
+    # +:rates+ (+:max_weight+ and +:cost+ of each weight break) price that
+    # billable weight. The boxes are chosen for the lowest total +:cost+, or
+    # billable weight without rates, and each packing reports its
+    # +:billable_weight+. As with several box types the bounds are nil, the
+    # result's +:strategy+ is the one the chosen boxes were filled with, and
+    # +time_limit_ms+ is turned away.
+    def pack(container: nil, containers: nil, carrier: nil, items:, **options)
+      return RustPacker.pack_for_carrier(containers, carrier, items, options) if containers && carrier
+      return RustPacker.pack_container_types(containers, items, options) if containers
+
+      RustPacker.pack(container, items, options)
     end
 
-    def find_smallest_containers(items:,max_count:)
-      possible_containers = []
-      invalid_containers  = []
-
-      min_vol = items.map { |h| h[:dimensions].inject(&:*) }.inject(&:+)
-      # order items from biggest to smallest
-      sorted_items = items.sort_by { |h| h[:dimensions].sort }.reverse
-
-      # base_container = sorted_items.first
-      based_container = sorted_items.first
-      return [based_container[:dimensions]] if sorted_items.size == 1
+    # Checks +packings+, as returned by +pack+, of +items+ in copies of
+    # +container+ and lists what is physically wrong with them, nothing if
+    # they are sound. Each violation names its +:violation+ (+:out_of_bounds+,
+    # +:overlap+, +:unknown_placement+, +:duplicate+, +:missing+,
+    # +:weight_mismatch+ or +:overweight+), the +:packing+ and the
+    # +:placement+, +:placements+ or +:item+ at fault, and a +:message+.
+    # Leave unpacked items out of +items+, and don't check packings made with
+    # +legacy_positions+. Pass the +given_axes+ the packings were made with.
+    def validate(container:, items:, packings:, given_axes: false)
+      RustPacker.validate(container, items, packings, { given_axes: given_axes })
+    end
 
-      find_possible_container(
-        possible_containers: possible_containers,
-        invalid_containers: invalid_containers,
-        container: based_container[:dimensions],
-        items: sorted_items.map {|i| i[:dimensions]},
-        item_index: 1,
-        min_vol: min_vol)
+    def find_smallest_container_with_limits(items:, limit_dimensions:)
+      RustPacker.find_smallest_containers(items, 5, limit_dimensions)[0]
+    end
 
-      count = 0;
-      containers = []
-      possible_containers.map { |a| a.sort }.sort_by { |a| [a.inject(&:*), a.inject(&:+)] }.each do |c|
-        packing = pack(
-          container: { dimensions: c },
-          items: items)
-        if packing[:packings].size == 1 && packing[:errors].size == 0
-          count += 1
-          containers.push(c)
-        end
-        break if count == max_count
-      end
-      ret_c = []
-      containers.each do |c|
-        ret_c.push check_container_is_bigger_than_greedy_box({dimensions: c}, items) ? item_greedy_box(items) : c
-      end
-      ret_c
+    def find_smallest_containers(items:, max_count:)
+      RustPacker.find_smallest_containers(items, max_count, nil)
     end
 
     def find_smallest_container(items:)
       find_smallest_containers(items: items, max_count: 1)[0]
     end
 
-    private
-
-    def std(contents)
-      n = contents.size
-      contents.map!(&:to_f)
-      mean = contents.reduce(&:+)/n
-      sum_sqr = contents.map {|x| x * x}.reduce(&:+)
-      Math.sqrt(((sum_sqr - n * mean * mean)/(n-1)).abs)
+    # Designs the cheapest box a box-on-demand machine can make for +items+,
+    # nil if none holds them all. Sizes are outer sizes:
+    # +max_dimensions+::  largest box the machine makes
+    # +min_dimensions+::  smallest box it makes
+    # +increment+::       sides are cut to multiples of this
+    # +wall_thickness+::  taken off both sides of every side to pack into
+    # +weight_limit+::    most the box may carry
+    # +board_cost+::      cost per unit of outer surface, 1 by default
+    # +void_cost+::       cost per unit of inner volume left empty, 0 by
+    #                     default
+    # Other options are passed on to +pack+. The result holds the
+    # +:inner_dimensions+, +:outer_dimensions+, +:cost+ and +:packing+.
+    def design_box(items:, max_dimensions:, min_dimensions: nil, increment: nil, wall_thickness: nil,
+                   weight_limit: nil, board_cost: nil, void_cost: nil, **options)
+      spec = {
+        max_dimensions: max_dimensions, min_dimensions: min_dimensions, increment: increment,
+        wall_thickness: wall_thickness, weight_limit: weight_limit, board_cost: board_cost, void_cost: void_cost
+      }
+      RustPacker.design_box(items, spec, options)
     end
 
-    def find_possible_container(possible_containers:,invalid_containers:,container:, items:, item_index:, min_vol:)
-      return unless items[item_index]
-      c_length, c_width, c_height = container.sort.reverse
-      b_length, b_width, b_height = items[item_index].sort.reverse
-      c_permutations = [
-        [c_width,  c_height, c_length],
-        [c_length, c_width,  c_height],
-        [c_length, c_height, c_width]
-      ]
-      b_permutations = [
-        [b_width,  b_height, b_length],
-        [b_length, b_width,  b_height],
-        [b_length, b_height, b_width]
-      ]
-
-      tmp_possible_containers = []
-      # (1) loops base_container 3 rotations
-      c_permutations.each do |c_perm|
-        # (2) try to puts items to 3 points, then it will create 3 different possible containers
-        b_permutations.each do |b_perm|
-          tmp_possible_containers << [  c_perm[0] + b_perm[0],     [c_perm[1], b_perm[1]].max, [c_perm[2], b_perm[2]].max]
-          tmp_possible_containers << [ [c_perm[0], b_perm[0]].max,  c_perm[1] + b_perm[1],     [c_perm[2], b_perm[2]].max]
-          tmp_possible_containers << [ [c_perm[0], b_perm[0]].max, [c_perm[1], b_perm[1]].max,  c_perm[2]+ b_perm[2]]
-        end
-      end
-      removed_tried_container = tmp_possible_containers.map { |a| a.sort }.uniq - possible_containers - invalid_containers
-
-      return unless removed_tried_container.any?
-      # (3) loop all container from smallest spaces to biggest space
-      removed_tried_container.sort_by { |a| [a.inject(&:*), a.inject(&:+)] }.each do |cont|
-        # (4) next unless l * w * h >= minimum_space
-        if cont.inject(&:*) >= min_vol
-          possible_containers << cont
-        # else
-          # puts "invalid: #{cont}"
-          # invalid_containers << cont
-          # find_possible_container(possible_containers: possible_containers, invalid_containers: invalid_containers, container: cont, items: items, item_index: item_index + 1, min_vol: min_vol)
-        end
-      end
-      # minimum_space = (removed_tried_container).sort_by { |a| [a.inject(&:*), a.inject(&:+)] }.first
-      minimum_std = removed_tried_container.sort_by { |a| [std(a), a.inject(&:*), a.inject(&:+)] }.first
-      [minimum_std].uniq.compact.each do |cont|
-        find_possible_container(possible_containers: possible_containers, invalid_containers: invalid_containers, container: cont, items: items, item_index: item_index + 1, min_vol: min_vol)
-      end
-    end
+    private
 
     def item_greedy_box(items)
       RustPacker.item_greedy_box(items)
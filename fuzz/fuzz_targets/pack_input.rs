@@ -0,0 +1,225 @@
+//! Feeds arbitrary Ruby-like containers, items and options through the
+//! adapter's own conversions into `pack_with_options`, and checks that bad
+//! input is turned away with an error pointing at an item that exists, and
+//! that whatever is accepted packs soundly.
+#![no_main]
+
+// only the conversions `pack` makes are fuzzed here
+#[allow(dead_code)]
+#[path = "../../src/convert.rs"]
+mod convert;
+
+use arbitrary::Arbitrary;
+use convert::{to_container, to_items, to_options, Value};
+use easy_box_packer_core::{pack_with_options, validate_with_options, Container, Dimensions, Item};
+use libfuzzer_sys::fuzz_target;
+
+/// Most items after expanding quantities, to keep runs fast.
+const MAX_ITEMS: usize = 32;
+
+/// Hash keys and symbols, mostly the ones the conversions look for.
+#[derive(Arbitrary, Clone, Debug)]
+enum Key {
+    Dimensions,
+    InnerDimensions,
+    Weight,
+    WeightLimit,
+    TareWeight,
+    MaxLoad,
+    Id,
+    Sku,
+    AllowedRotations,
+    KeepUpright,
+    Stackable,
+    Quantity,
+    Strategy,
+    Merit,
+    MinSupport,
+    CogEnvelope,
+    GreedyFallback,
+    LegacyPositions,
+    GivenAxes,
+    Seed,
+    Spaces,
+    ExtremePoints,
+    MaximalSpaces,
+    Layers,
+    Exact,
+    GreedyBox,
+    ResidualSpace,
+    FreeVolume,
+    Other(String),
+}
+
+impl Key {
+    fn name(&self) -> String {
+        let name = match self {
+            Key::Dimensions => "dimensions",
+            Key::InnerDimensions => "inner_dimensions",
+            Key::Weight => "weight",
+            Key::WeightLimit => "weight_limit",
+            Key::TareWeight => "tare_weight",
+            Key::MaxLoad => "max_load",
+            Key::Id => "id",
+            Key::Sku => "sku",
+            Key::AllowedRotations => "allowed_rotations",
+            Key::KeepUpright => "keep_upright",
+            Key::Stackable => "stackable",
+            Key::Quantity => "quantity",
+            Key::Strategy => "strategy",
+            Key::Merit => "merit",
+            Key::MinSupport => "min_support",
+            Key::CogEnvelope => "cog_envelope",
+            Key::GreedyFallback => "greedy_fallback",
+            Key::LegacyPositions => "legacy_positions",
+            Key::GivenAxes => "given_axes",
+            Key::Seed => "seed",
+            Key::Spaces => "spaces",
+            Key::ExtremePoints => "extreme_points",
+            Key::MaximalSpaces => "maximal_spaces",
+            Key::Layers => "layers",
+            Key::Exact => "exact",
+            Key::GreedyBox => "greedy_box",
+            Key::ResidualSpace => "residual_space",
+            Key::FreeVolume => "free_volume",
+            Key::Other(name) => name,
+        };
+        name.to_string()
+    }
+}
+
+/// A Ruby object as Ruby would hand it over.
+#[derive(Arbitrary, Clone, Debug)]
+enum Ruby {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Symbol(Key),
+    Array(Vec<Ruby>),
+    Hash(Vec<(Key, Ruby)>),
+    /// Anything else, say a Range.
+    Other,
+}
+
+impl Value for Ruby {
+    fn is_nil(&self) -> bool {
+        matches!(self, Ruby::Nil)
+    }
+
+    fn boolean(&self) -> Option<bool> {
+        match self {
+            Ruby::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    fn integer(&self) -> Option<i64> {
+        match self {
+            Ruby::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    fn float(&self) -> Option<f64> {
+        match self {
+            Ruby::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    fn string(&self) -> Option<String> {
+        match self {
+            Ruby::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> Option<String> {
+        match self {
+            Ruby::Symbol(key) => Some(key.name()),
+            _ => None,
+        }
+    }
+
+    fn array(&self) -> Option<Vec<Ruby>> {
+        match self {
+            Ruby::Array(values) => Some(values.clone()),
+            _ => None,
+        }
+    }
+
+    fn is_hash(&self) -> bool {
+        matches!(self, Ruby::Hash(_))
+    }
+
+    fn at(&self, key: &str) -> Ruby {
+        match self {
+            // a later key replaces an earlier one, as in a Ruby hash literal
+            Ruby::Hash(pairs) => pairs.iter().rev().find(|(k, _)| k.name() == key).map_or(Ruby::Nil, |(_, value)| value.clone()),
+            _ => Ruby::Nil,
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    container: Ruby,
+    items: Ruby,
+    options: Ruby,
+}
+
+/// Whether every number is small enough, and big enough, for the validator's
+/// fixed tolerance to tell rounding from real faults.
+fn is_moderate(container: &Container, items: &[Item]) -> bool {
+    let moderate = |value: f64| value == 0.0 || (1e-3..=1e6).contains(&value.abs());
+    let sides = |dimensions: &Dimensions| dimensions.dimensions.iter().all(|&side| moderate(side));
+    sides(&container.dimensions)
+        && moderate(container.tare_weight)
+        && container.weight_limit.bound().is_none_or(moderate)
+        && items.iter().all(|item| sides(&item.dimensions) && item.weight.is_none_or(moderate))
+}
+
+fuzz_target!(|input: Input| {
+    let given = input.items.array().map_or(0, |items| items.len());
+    let converted = to_options(&input.options).and_then(|options| Ok((options, to_container(&input.container, None)?, to_items(&input.items)?)));
+    let (mut options, container, mut items) = match converted {
+        Ok(converted) => converted,
+        Err(error) => {
+            assert!(error.item_index().is_none_or(|i| i < given), "{}", error);
+            return;
+        }
+    };
+    // keep runs fast, the limits themselves are not under test
+    options.time_limit_ms = None;
+    options.node_limit = Some(options.node_limit.unwrap_or(1_000).min(1_000));
+    items.truncate(MAX_ITEMS);
+
+    let result = match pack_with_options(&container, &items, &options) {
+        Ok(result) => result,
+        Err(error) => {
+            assert!(error.item_index().is_none_or(|i| i < items.len()), "{}", error);
+            return;
+        }
+    };
+    assert_eq!(result.errors.len(), result.unpacked.len());
+    if options.legacy_positions || !is_moderate(&container, &items) {
+        return;
+    }
+    // identical items are interchangeable, so leave out one per unpacked item
+    let mut packed = items.clone();
+    for unpacked in &result.unpacked {
+        let same = |item: &Item| {
+            item.id == unpacked.item.id
+                && item.instance == unpacked.item.instance
+                && item.dimensions == unpacked.item.dimensions
+                && item.weight == unpacked.item.weight
+        };
+        if let Some(i) = packed.iter().position(same) {
+            packed.remove(i);
+        }
+    }
+    let violations = validate_with_options(&container, &packed, &result.packings, &options);
+    assert!(violations.is_empty(), "{:?}", violations);
+});
@@ -15,19 +15,92 @@ describe '.pack' do
     expect(packings[:packings][0][:weight]).to eql(47.0)
     expect(packings[:packings][0][:placements].length).to eql(1)
     expect(packings[:packings][0][:placements][0][:dimensions]).to eq([2, 3, 5])
-    expect(packings[:packings][0][:placements][0][:position]).to eq([20, 15, 13])
+    expect(packings[:packings][0][:placements][0][:position]).to eq([0, 0, 0])
     expect(packings[:packings][1][:weight]).to eql(47.0)
     expect(packings[:packings][1][:placements].length).to eql(1)
     expect(packings[:packings][1][:placements][0][:dimensions]).to eq([2, 3, 5])
-    expect(packings[:packings][1][:placements][0][:position]).to eq([20, 15, 13])
+    expect(packings[:packings][1][:placements][0][:position]).to eq([0, 0, 0])
     expect(packings[:packings][2][:weight]).to eql(31.0)
     expect(packings[:packings][2][:placements].length).to eql(2)
     expect(packings[:packings][2][:placements][0][:dimensions]).to eq([1, 1, 4])
+    expect(packings[:packings][2][:placements][0][:position]).to eq([0, 0, 0])
+    expect(packings[:packings][2][:placements][1][:dimensions]).to eq([1, 3, 3])
+    expect(packings[:packings][2][:placements][1][:position]).to eq([0, 1, 0])
+    expect(packings[:packings][2][:placements][1][:rotation]).to eq([2, 0, 1])
+  end
+
+  it 'checks support against real positions with legacy_positions' do
+    packings = EasyBoxPacker.pack(
+      container: { dimensions: [10, 10, 10] },
+      items: [{ dimensions: [10, 10, 5], weight: 1 }, { dimensions: [5, 10, 5], weight: 1 }],
+      legacy_positions: true,
+      min_support: 1
+    )
+    expect(packings[:packings].length).to eql(1)
+    expect(packings[:packings][0][:placements].map { |p| p[:support] }).to eql([1.0, 1.0])
+  end
+
+  it 'stacks items up the last container dimension with given_axes' do
+    packings = EasyBoxPacker.pack(container: { dimensions: [10, 10, 40] }, items: Array.new(4) { { dimensions: [10, 10, 10], weight: 1 } }, min_support: 1, given_axes: true)
+    expect(packings[:packings][0][:placements].map { |p| p[:position] }).to eq([[0, 0, 0], [0, 0, 10], [0, 0, 20], [0, 0, 30]])
+  end
+
+  it 'reports placements along the container sides sorted longest first' do
+    packings = EasyBoxPacker.pack(container: { dimensions: [15, 20, 13] }, items: [{ dimensions: [15, 1, 1] }])
+    expect(packings[:packings][0][:placements][0][:dimensions]).to eq([1, 15, 1])
+    expect(packings[:packings][0][:spaces].map { |space| space[:dimensions] }).to eq([[19, 15, 13], [1, 15, 12]])
+  end
+
+  it 'reports placements along the container dimensions as given with given_axes' do
+    packings = EasyBoxPacker.pack(container: { dimensions: [15, 20, 13] }, items: [{ dimensions: [15, 1, 1] }], given_axes: true)
+    expect(packings[:packings][0][:placements][0][:dimensions]).to eq([15, 1, 1])
+    expect(packings[:packings][0][:spaces].map { |space| space[:dimensions] }).to eq([[15, 19, 13], [15, 1, 12]])
+  end
+
+  it 'reports the free space extents as position with legacy_positions' do
+    packings = EasyBoxPacker.pack(
+      container: { dimensions: [15, 20, 13], weight_limit: 50 },
+      items: [
+        { dimensions: [2, 3, 5], weight: 47 },
+        { dimensions: [2, 3, 5], weight: 47 },
+        { dimensions: [3, 3, 1], weight: 24 },
+        { dimensions: [1, 1, 4], weight: 7 },
+      ],
+      legacy_positions: true
+    )
+
+    expect(packings[:packings][0][:placements][0][:position]).to eq([20, 15, 13])
+    expect(packings[:packings][1][:placements][0][:position]).to eq([20, 15, 13])
     expect(packings[:packings][2][:placements][0][:position]).to eq([20, 15, 13])
     expect(packings[:packings][2][:placements][1][:dimensions]).to eq([1, 3, 3])
     expect(packings[:packings][2][:placements][1][:position]).to eq([1, 14, 13])
   end
 
+  it 'rejects legacy_positions with strategies that place items outside free spaces' do
+    expect {
+      EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [1, 1, 1] }], strategy: :layers, legacy_positions: true)
+    }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :legacy_positions must be false unless packing with :spaces or :exact along sorted sides')
+  end
+
+  it 'rejects legacy_positions with given_axes' do
+    expect {
+      EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [1, 1, 1] }], given_axes: true, legacy_positions: true)
+    }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :legacy_positions must be false unless packing with :spaces or :exact along sorted sides')
+  end
+
+  it 'reports the remaining free spaces' do
+    packings = EasyBoxPacker.pack(
+      container: { dimensions: [10, 10, 10] },
+      items: [
+        { dimensions: [10, 10, 5] },
+        { dimensions: [5, 10, 5] }
+      ]
+    )
+
+    expect(packings[:packings][0][:free_volume]).to eql(250.0)
+    expect(packings[:packings][0][:spaces]).to eq([{ dimensions: [5, 5, 10], position: [5, 5, 0], volume: 250 }])
+  end
+
   it 'no weight given' do
      packings = EasyBoxPacker.pack(
       container: { dimensions: [13, 15, 20] },
@@ -169,6 +242,25 @@ describe '.pack' do
       expect(packings[:packings].length).to eql(1)
     end
 
+    it 'case 9b, packs weighted items into one box when the container has no weight limit' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10] },
+        items: Array.new(8) {{ dimensions: [5, 5, 5], weight: 100 }}
+      )
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:weight]).to eql(800.0)
+      expect(packings[:packings][0][:placements].length).to eql(8)
+    end
+
+    it 'case 9c, treats a zero weight limit as no limit' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10], weight_limit: 0 },
+        items: Array.new(8) {{ dimensions: [5, 5, 5], weight: 100 }}
+      )
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:errors]).to eql([])
+    end
+
     it 'case 10, gives error that the item is too heavy' do
       packings = EasyBoxPacker.pack(
         container: { dimensions: [46.5, 32, 15], weight_limit: 10 },
@@ -180,6 +272,579 @@ describe '.pack' do
       expect(packings[:errors]).to eql(["Item: {:dimensions=>[7, 8, 9], weight: 100} is too heavy for container"])
     end
   end
+
+  context 'extreme points strategy' do
+    [:residual_space, :free_volume].each do |merit|
+      it "packs with the #{merit} merit" do
+        packings = EasyBoxPacker.pack(
+          container: { dimensions: [10, 10, 10] },
+          items: Array.new(8) {{ dimensions: [5, 5, 5] }},
+          strategy: :extreme_points,
+          merit: merit
+        )
+        expect(packings[:packings].length).to eql(1)
+        expect(packings[:packings][0][:placements].map { |p| p[:position] }).to match_array(
+          [[0, 0, 0], [5, 0, 0], [0, 5, 0], [5, 5, 0], [0, 0, 5], [5, 0, 5], [0, 5, 5], [5, 5, 5]]
+        )
+        expect(packings[:packings][0][:free_volume]).to eql(0.0)
+      end
+    end
+
+    it 'rejects an unknown strategy' do
+      expect {
+        EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [], strategy: :magic)
+      }.to raise_error(EasyBoxPacker::InvalidInput)
+    end
+  end
+
+  context 'maximal spaces strategy' do
+    let(:items) { [{ dimensions: [2, 7, 8] }, { dimensions: [6, 5, 6] }, { dimensions: [4, 10, 8] }] }
+
+    it 'needs two boxes when free space is split up' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: items)
+      expect(packings[:packings].length).to eql(2)
+    end
+
+    it 'uses room spanning several split spaces' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: items, strategy: :maximal_spaces)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].map { |p| p[:position] }).to eq([[0, 0, 0], [8, 0, 0], [0, 4, 0]])
+    end
+  end
+
+  context 'layers strategy' do
+    let(:items) { Array.new(48) {{ dimensions: [10, 5, 10] }} }
+
+    it 'fills the container with whole layers of identical items' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [40, 30, 20] }, items: items, strategy: :layers)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].map { |p| p[:position][2] }.uniq).to eq([0, 10])
+      expect(packings[:packings][0][:free_volume]).to eql(0.0)
+    end
+
+    it 'packs the remainder around the layers' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [40, 30, 25] },
+        items: items + [{ dimensions: [3, 3, 3] }],
+        strategy: :layers
+      )
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].last[:dimensions]).to eq([3, 3, 3])
+      expect(packings[:packings][0][:placements].last[:position]).to eq([0, 0, 20])
+    end
+
+    it 'leaves items too few for a layer to the free spaces' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [20, 20, 20] },
+        items: [{ dimensions: [10, 10, 10] }, { dimensions: [9, 9, 9] }, { dimensions: [8, 8, 8] }],
+        strategy: :layers
+      )
+      expect(packings[:packings].length).to eql(1)
+    end
+  end
+
+  context 'orientation constraints' do
+    it 'keeps upright items upright' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 2] },
+        items: [{ dimensions: [2, 2, 10], keep_upright: true }, { dimensions: [2, 2, 10] }]
+      )
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].length).to eql(1)
+      expect(packings[:errors]).to eql(['Item: {:dimensions=>[2, 2, 10]} cannot be placed in container'])
+    end
+
+    it 'keeps items upright along the box height as given with given_axes' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [10, 10, 40] }, items: [{ dimensions: [5, 5, 30], keep_upright: true }], given_axes: true)
+      expect(packings[:packings][0][:placements][0][:rotation]).to eql([1, 0, 2])
+      expect(packings[:errors]).to eql([])
+    end
+
+    it 'reads allowed rotations along the box dimensions as given with given_axes' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [15, 20, 13] },
+        items: [{ dimensions: [1, 20, 1], allowed_rotations: [[0, 1, 2]] }],
+        given_axes: true
+      )
+      expect(packings[:packings][0][:placements][0][:rotation]).to eql([0, 1, 2])
+      expect(packings[:errors]).to eql([])
+    end
+
+    %i[spaces extreme_points maximal_spaces layers exact].each do |strategy|
+      it "only uses allowed rotations with #{strategy}" do
+        packings = EasyBoxPacker.pack(
+          container: { dimensions: [10, 10, 10] },
+          items: [{ dimensions: [4, 3, 2], allowed_rotations: [[1, 0, 2]] }],
+          strategy: strategy
+        )
+        placement = packings[:packings][0][:placements][0]
+        expect(placement[:rotation]).to eql([1, 0, 2])
+        expect(placement[:dimensions]).to eql([3.0, 4.0, 2.0])
+      end
+    end
+
+    it 'rejects rotations that are not permutations' do
+      expect {
+        EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [4, 3, 2], allowed_rotations: [[0, 0, 2]] }])
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'item 0: rotation [0, 0, 2] must be a permutation of [0, 1, 2]')
+    end
+  end
+
+  context 'stacking limits' do
+    let(:container) { { dimensions: [10, 10, 10] } }
+    let(:top) { { dimensions: [10, 10, 4], weight: 4, keep_upright: true } }
+
+    it 'reports the load on each item' do
+      packings = EasyBoxPacker.pack(container: container, items: [{ dimensions: [10, 10, 6], weight: 1, keep_upright: true, max_load: 10 }, top])
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].map { |p| [p[:position], p[:load]] }).to eql([[[0.0, 0.0, 0.0], 4.0], [[0.0, 0.0, 6.0], 0.0]])
+    end
+
+    it 'does not overload items' do
+      packings = EasyBoxPacker.pack(container: container, items: [{ dimensions: [10, 10, 6], weight: 1, keep_upright: true, max_load: 3 }, top])
+      expect(packings[:packings].length).to eql(2)
+    end
+
+    it 'puts nothing on items that are not stackable' do
+      items = [{ dimensions: [10, 10, 6], weight: 1, keep_upright: true, stackable: false }, top]
+      expect(EasyBoxPacker.pack(container: container, items: items)[:packings].length).to eql(2)
+      packings = EasyBoxPacker.pack(container: container, items: items, strategy: :exact)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].map { |p| p[:position] }).to eql([[0.0, 0.0, 0.0], [0.0, 0.0, 4.0]])
+    end
+  end
+
+  context 'minimum support' do
+    let(:container) { { dimensions: [10, 10, 10] } }
+    let(:items) { [{ dimensions: [3, 3, 9.5], keep_upright: true }, { dimensions: [9, 9, 0.5], keep_upright: true }] }
+
+    it 'reports how much of each item is supported' do
+      packings = EasyBoxPacker.pack(container: container, items: items, strategy: :maximal_spaces)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements].map { |p| p[:support].round(3) }).to eql([1.0, 0.111])
+    end
+
+    it 'does not let items overhang too far' do
+      packings = EasyBoxPacker.pack(container: container, items: items, strategy: :maximal_spaces, min_support: 0.7)
+      expect(packings[:packings].length).to eql(2)
+      expect(packings[:packings].map { |p| p[:placements][0][:support] }).to eql([1.0, 1.0])
+    end
+
+    it 'rejects ratios above 1' do
+      expect {
+        EasyBoxPacker.pack(container: container, items: items, min_support: 70)
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :min_support must be a number between 0 and 1')
+    end
+  end
+
+  context 'centre of gravity' do
+    let(:container) { { dimensions: [10, 10, 10] } }
+    let(:items) { [{ dimensions: [10, 10, 5], weight: 10, keep_upright: true }, { dimensions: [5, 5, 5], weight: 10 }] }
+
+    it 'reports the centre of gravity of each packing' do
+      packings = EasyBoxPacker.pack(container: container, items: items)
+      expect(packings[:packings][0][:centre_of_gravity]).to eql([3.75, 3.75, 5.0])
+      expect(EasyBoxPacker.pack(container: container, items: [{ dimensions: [1, 1, 1] }])[:packings][0][:centre_of_gravity]).to be_nil
+    end
+
+    it 'slides the load to the centre' do
+      packings = EasyBoxPacker.pack(container: container, items: [{ dimensions: [2, 2, 2], weight: 1 }], cog_envelope: [1, 1, 5])
+      expect(packings[:packings][0][:placements][0][:position]).to eql([4.0, 4.0, 0.0])
+      expect(packings[:packings][0][:centre_of_gravity]).to eql([5.0, 5.0, 1.0])
+    end
+
+    it 'keeps every packing within the envelope' do
+      packings = EasyBoxPacker.pack(container: container, items: items, cog_envelope: [1, 1, 5])
+      expect(packings[:packings].length).to eql(2)
+      expect(packings[:packings].map { |p| p[:centre_of_gravity] }).to eql([[5.0, 5.0, 2.5], [5.0, 5.0, 2.5]])
+    end
+  end
+
+  context 'several container types' do
+    let(:containers) do
+      [
+        { dimensions: [10, 10, 10], cost: 5 },
+        { dimensions: [20, 20, 20], cost: 20 },
+        { dimensions: [6, 6, 6], inner_dimensions: [5, 5, 5], cost: 1, stock: 1 }
+      ]
+    end
+
+    it 'picks the cheapest mix of containers' do
+      packings = EasyBoxPacker.pack(containers: containers, items: Array.new(9) { { dimensions: [5, 5, 5] } })
+      expect(packings[:packings].map { |p| [p[:container_type], p[:placements].length] }).to eql([[0, 8], [2, 1]])
+      expect(packings[:cost]).to eql(6.0)
+      expect(packings[:errors]).to eql([])
+      expect(packings[:lower_bounds]).to be_nil
+      expect(packings[:gap]).to be_nil
+    end
+
+    it 'reports the strategy each container was filled with' do
+      packings = EasyBoxPacker.pack(containers: containers, items: Array.new(9) { { dimensions: [5, 5, 5] } }, strategy: :layers)
+      expect(packings[:strategy]).to eql(:spaces)
+    end
+
+    it 'rejects a time limit' do
+      expect {
+        EasyBoxPacker.pack(containers: containers, items: [{ dimensions: [5, 5, 5] }], time_limit_ms: 50)
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :time_limit_ms must be nil when packing into several container types')
+    end
+
+    it 'reports items no container is left for' do
+      packings = EasyBoxPacker.pack(containers: [containers[2]], items: Array.new(2) { { dimensions: [5, 5, 5] } })
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:errors]).to eql(['Item: {:dimensions=>[5, 5, 5]} cannot be placed: no container left in stock'])
+    end
+
+    it 'requires a cost' do
+      expect {
+        EasyBoxPacker.pack(containers: [{ dimensions: [10, 10, 10] }], items: [{ dimensions: [5, 5, 5] }])
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'container: missing :cost')
+    end
+  end
+
+  context 'item ids and quantities' do
+    it 'expands quantities and echoes ids in placements' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10] },
+        items: [{ dimensions: [5, 5, 5], sku: 'SKU-123', quantity: 3 }, { dimensions: [5, 5, 10], id: 7 }]
+      )
+      placements = packings[:packings][0][:placements]
+      expect(placements.length).to eql(4)
+      expect(placements.map { |placement| [placement[:id], placement[:instance]] })
+        .to eq([['7', 0], ['SKU-123', 0], ['SKU-123', 1], ['SKU-123', 2]])
+    end
+
+    it 'echoes ids in errors' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10] },
+        items: [{ dimensions: [20, 5, 5], id: 'SKU-9', quantity: 2 }]
+      )
+      expect(packings[:errors]).to eq([
+        'Item: {:id=>"SKU-9", :instance=>0, :dimensions=>[20, 5, 5]} cannot be placed in container',
+        'Item: {:id=>"SKU-9", :instance=>1, :dimensions=>[20, 5, 5]} cannot be placed in container'
+      ])
+    end
+
+    it 'reports invalid input against the hash given' do
+      expect do
+        EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [1, 1, 1], quantity: 3 }, { dimensions: [0, 1, 1] }])
+      end.to raise_error(EasyBoxPacker::InvalidInput) { |error| expect(error.item_index).to eql(1) }
+    end
+
+    [0, 10_001].each do |quantity|
+      it "rejects a quantity of #{quantity}" do
+        expect do
+          EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [1, 1, 1] }, { dimensions: [1, 1, 1], quantity: quantity }])
+        end.to raise_error(EasyBoxPacker::InvalidInput, 'item 1: :quantity must be an Integer from 1 to 10000') { |error| expect(error.item_index).to eql(1) }
+      end
+    end
+  end
+
+  context 'greedy box' do
+    let(:items) { Array.new(67) { { dimensions: [36, 27, 0.3] } } }
+
+    it 'stacks the items in one box when the strategy needs more' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [36, 27, 20.1] }, items: items)
+      expect(packings[:strategy]).to eq(:greedy_box)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:placements][0][:position]).to eq([0.0, 0.0, 0.0])
+      expect(packings[:packings][0][:placements][-1][:position][2]).to be_within(1e-9).of(19.8)
+    end
+
+    it 'keeps the packings without the fallback' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [36, 27, 20.1] }, items: items, greedy_fallback: false)
+      expect(packings[:strategy]).to eq(:spaces)
+      expect(packings[:packings].length).to eql(2)
+    end
+
+    it 'can be asked for' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [20, 20, 21] },
+        items: [{ dimensions: [10, 20, 11] }, { dimensions: [20, 20, 5] }, { dimensions: [20, 20, 4] }, { dimensions: [20, 20, 1] }],
+        strategy: :greedy_box
+      )
+      expect(packings[:strategy]).to eq(:greedy_box)
+      expect(packings[:packings][0][:placements].map { |placement| placement[:position][2] }).to eq([0.0, 5.0, 9.0, 10.0])
+    end
+  end
+
+  context 'unpacked items' do
+    it 'lists why each item was left out' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 20, 30], weight_limit: 50 },
+        items: [
+          { dimensions: [5, 5, 5], weight: 60, id: 'heavy' },
+          { dimensions: [5, 5, 31], weight: 1 },
+          { dimensions: [25, 5, 5], weight: 1, allowed_rotations: [[0, 1, 2]] },
+          { dimensions: [5, 5, 5], weight: 1 }
+        ],
+        given_axes: true
+      )
+      expect(packings[:unpacked].map { |unpacked| [unpacked[:reason], unpacked[:limit]] })
+        .to eq([[:too_large, 30.0], [:violates_orientation, 10.0], [:too_heavy, 50.0]])
+      expect(packings[:unpacked][2]).to include(id: 'heavy', instance: 0, dimensions: [5.0, 5.0, 5.0], weight: 60.0)
+      expect(packings[:errors].length).to eql(3)
+    end
+
+    it 'tells when a container type is out of stock' do
+      packings = EasyBoxPacker.pack(
+        containers: [{ dimensions: [10, 10, 10], cost: 1, stock: 1 }],
+        items: Array.new(2) { { dimensions: [10, 10, 10] } }
+      )
+      expect(packings[:unpacked].map { |unpacked| unpacked[:reason] }).to eq([:exceeds_stock])
+    end
+  end
+
+  context 'inner and outer dimensions' do
+    let(:items) { Array.new(8) { { dimensions: [5, 5, 5], weight: 5 } } }
+
+    it 'packs into the inner dimensions and reports the outer ones' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [11, 11, 11], inner_dimensions: [10, 10, 10], weight_limit: 50, tare_weight: 10 },
+        items: items
+      )
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:weight]).to eql(40.0)
+      expect(packings[:packings][0][:gross_weight]).to eql(50.0)
+      expect(packings[:packings][0][:outer_dimensions]).to eq([11.0, 11.0, 11.0])
+    end
+
+    it 'checks the weight limit against the gross weight' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [11, 11, 11], inner_dimensions: [10, 10, 10], weight_limit: 50, tare_weight: 15 },
+        items: items
+      )
+      expect(packings[:packings].map { |packing| packing[:gross_weight] }).to eq([50.0, 20.0])
+    end
+
+    it 'rejects inner dimensions bigger than the outer ones' do
+      expect {
+        EasyBoxPacker.pack(container: { dimensions: [11, 11, 11], inner_dimensions: [10, 12, 10] }, items: items)
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :inner_dimensions must be no bigger than :dimensions')
+    end
+  end
+
+  context 'dimensional weight' do
+    let(:containers) do
+      [
+        { dimensions: [10, 10, 10] },
+        { dimensions: [20, 20, 20] },
+        { dimensions: [20, 10, 10], weight_limit: 30 }
+      ]
+    end
+    let(:items) { Array.new(10) { { dimensions: [5, 5, 5], weight: 2 } } }
+
+    it 'picks the boxes with the lowest billable weight' do
+      packings = EasyBoxPacker.pack(containers: containers, carrier: { dim_divisor: 100 }, items: items)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:packings][0][:container_type]).to eql(2)
+      expect(packings[:packings][0][:billable_weight]).to eql(20.0)
+      expect(packings[:cost]).to eql(20.0)
+      expect(packings[:strategy]).to eql(:spaces)
+      expect(packings[:lower_bounds]).to be_nil
+    end
+
+    it 'prices the billable weight with rates' do
+      rates = [{ max_weight: 10, cost: 5 }, { max_weight: 30, cost: 8 }, { max_weight: 100, cost: 20 }]
+      packings = EasyBoxPacker.pack(containers: containers, carrier: { dim_divisor: 100, rates: rates }, items: items)
+      expect(packings[:cost]).to eql(8.0)
+      expect(packings[:errors]).to be_empty
+    end
+
+    it 'rejects a time limit' do
+      expect { EasyBoxPacker.pack(containers: containers, carrier: { dim_divisor: 100 }, items: items, time_limit_ms: 50) }
+        .to raise_error(EasyBoxPacker::InvalidInput, 'container: :time_limit_ms must be nil when packing into several container types')
+    end
+
+    it 'needs a divisor' do
+      expect { EasyBoxPacker.pack(containers: containers, carrier: {}, items: items) }
+        .to raise_error(EasyBoxPacker::InvalidInput, /dim_divisor/)
+    end
+  end
+
+  context 'lower bounds' do
+    it 'counts items that cannot share a container' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10] },
+        items: [{ dimensions: [6, 6, 6] }, { dimensions: [6, 6, 6] }, { dimensions: [2, 2, 2] }]
+      )
+      expect(packings[:packings].length).to eql(2)
+      expect(packings[:lower_bounds]).to eql(volume: 1, l1: 2, l2: 2, weight: 0, best: 2)
+      expect(packings[:gap]).to eql(0.0)
+    end
+
+    it 'counts items too heavy to share a container' do
+      packings = EasyBoxPacker.pack(
+        container: { dimensions: [10, 10, 10], weight_limit: 10 },
+        items: Array.new(3) { { dimensions: [1, 1, 1], weight: 6 } }
+      )
+      expect(packings[:packings].length).to eql(3)
+      expect(packings[:lower_bounds]).to eql(volume: 1, l1: 3, l2: 3, weight: 2, best: 3)
+      expect(packings[:gap]).to eql(0.0)
+    end
+  end
+
+  context 'exact search' do
+    let(:container) { { dimensions: [10, 10, 10] } }
+    let(:items) do
+      [
+        { dimensions: [10, 10, 5] },
+        { dimensions: [10, 10, 4] },
+        { dimensions: [10, 10, 3] },
+        { dimensions: [10, 10, 3] },
+        { dimensions: [10, 10, 3] },
+        { dimensions: [10, 10, 2] }
+      ]
+    end
+
+    it 'beats first fit and proves it' do
+      expect(EasyBoxPacker.pack(container: container, items: items)[:packings].length).to eql(3)
+      packings = EasyBoxPacker.pack(container: container, items: items, strategy: :exact)
+      expect(packings[:packings].length).to eql(2)
+      expect(packings[:proven_optimal]).to be true
+    end
+
+    it 'falls back to first fit when out of nodes' do
+      packings = EasyBoxPacker.pack(container: container, items: items, strategy: :exact, node_limit: 1)
+      expect(packings[:packings].length).to eql(3)
+      expect(packings[:proven_optimal]).to be false
+    end
+
+    it 'proves nothing when items are left out' do
+      packings = EasyBoxPacker.pack(container: container, items: [{ dimensions: [5, 5, 5] }, { dimensions: [11, 1, 1] }], strategy: :exact)
+      expect(packings[:packings].length).to eql(1)
+      expect(packings[:unpacked].length).to eql(1)
+      expect(packings[:proven_optimal]).to be false
+    end
+  end
+
+  context 'improvement phase' do
+    let(:items) do
+      [
+        { dimensions: [10, 20, 11] },
+        { dimensions: [20, 20, 5]  },
+        { dimensions: [20, 20, 4]  },
+        { dimensions: [20, 20, 1]  }
+      ]
+    end
+
+    it 'never does worse than the plain packing' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [20, 30, 10] }, items: items, time_limit_ms: 50, seed: 1)
+      expect(packings[:packings].length).to be <= 2
+      expect(packings[:packings].sum { |p| p[:placements].length }).to eql(4)
+    end
+
+    it 'places items as the strategy asked for does' do
+      packings = EasyBoxPacker.pack(container: { dimensions: [20, 30, 10] }, items: items, strategy: :maximal_spaces, time_limit_ms: 50, seed: 1)
+      expect(packings[:strategy]).to eql(:maximal_spaces)
+      expect(packings[:packings].sum { |p| p[:placements].length }).to eql(4)
+    end
+
+    it 'rejects a negative time limit' do
+      expect {
+        EasyBoxPacker.pack(container: { dimensions: [20, 30, 10] }, items: items, time_limit_ms: -1)
+      }.to raise_error(EasyBoxPacker::InvalidInput)
+    end
+  end
+
+  context 'validation' do
+    let(:items) { Array.new(2) { { dimensions: [5, 5, 5], weight: 5 } } }
+
+    def placement(position)
+      { dimensions: [5, 5, 5], position: position, weight: 5 }
+    end
+
+    it 'finds nothing wrong with what pack returns' do
+      container = { dimensions: [10, 10, 10], weight_limit: 10 }
+      packings = EasyBoxPacker.pack(container: container, items: items)
+      expect(EasyBoxPacker.validate(container: container, items: items, packings: packings[:packings])).to eq([])
+    end
+
+    it 'reports overlapping and overweight packings' do
+      violations = EasyBoxPacker.validate(
+        container: { dimensions: [10, 10, 10], weight_limit: 8 },
+        items: items,
+        packings: [{ weight: 10, placements: [placement([0, 0, 0]), placement([2, 0, 0])] }]
+      )
+      expect(violations.map { |violation| violation[:violation] }).to eq(%i[overlap overweight])
+      expect(violations[0]).to include(packing: 0, placements: [0, 1])
+      expect(violations[1]).to include(weight: 10.0, limit: 8.0)
+    end
+
+    it 'reports placements outside the box, wrong weights and missing items' do
+      violations = EasyBoxPacker.validate(
+        container: { dimensions: [10, 10, 10] },
+        items: items,
+        packings: [{ weight: 10, placements: [placement([6, 0, 0])] }]
+      )
+      expect(violations.map { |violation| violation[:violation] }).to eq(%i[out_of_bounds weight_mismatch missing])
+      expect(violations[2]).to include(packing: nil, item: 1, message: 'item 1 is not placed')
+    end
+  end
+
+  context 'invalid input' do
+    def expect_invalid_input(item_index, container: { dimensions: [10, 10, 10] }, items:)
+      expect { EasyBoxPacker.pack(container: container, items: items) }.to raise_error(EasyBoxPacker::InvalidInput) { |e|
+        expect(e.item_index).to eql(item_index)
+      }
+    end
+
+    it 'raises for an item without dimensions' do
+      expect_invalid_input(1, items: [{ dimensions: [1, 1, 1] }, { weight: 2 }])
+    end
+
+    it 'raises for a string dimension' do
+      expect_invalid_input(0, items: [{ dimensions: [1, '1', 1] }])
+    end
+
+    it 'raises for a two element dimension array' do
+      expect_invalid_input(0, items: [{ dimensions: [1, 1] }])
+    end
+
+    it 'raises for an item that is not a hash' do
+      expect_invalid_input(2, items: [{ dimensions: [1, 1, 1] }, { dimensions: [1, 1, 1] }, [1, 1, 1]])
+    end
+
+    it 'raises for zero, negative and NaN dimensions' do
+      expect_invalid_input(0, items: [{ dimensions: [0, 1, 1] }])
+      expect_invalid_input(0, items: [{ dimensions: [-1, 1, 1] }])
+      expect_invalid_input(0, items: [{ dimensions: [Float::NAN, 1, 1] }])
+    end
+
+    it 'raises for a non-finite weight' do
+      expect_invalid_input(0, items: [{ dimensions: [1, 1, 1], weight: Float::INFINITY }])
+    end
+
+    it 'raises for negative weights and load limits' do
+      expect_invalid_input(0, items: [{ dimensions: [1, 1, 1], weight: -1 }])
+      expect_invalid_input(0, items: [{ dimensions: [1, 1, 1], max_load: -1 }])
+      expect_invalid_input(nil, container: { dimensions: [10, 10, 10], weight_limit: -5 }, items: [{ dimensions: [1, 1, 1] }])
+      expect_invalid_input(nil, container: { dimensions: [10, 10, 10], tare_weight: -1 }, items: [{ dimensions: [1, 1, 1] }])
+    end
+
+    it 'raises for a negative centre of gravity envelope' do
+      expect {
+        EasyBoxPacker.pack(container: { dimensions: [10, 10, 10] }, items: [{ dimensions: [1, 1, 1] }], cog_envelope: [1, -1, 1])
+      }.to raise_error(EasyBoxPacker::InvalidInput, 'container: :cog_envelope must be non-negative numbers')
+    end
+
+    it 'raises without an item index for an invalid container' do
+      expect_invalid_input(nil, container: { dimensions: [10, 10] }, items: [{ dimensions: [1, 1, 1] }])
+    end
+  end
+end
+
+describe '.check_container_is_bigger_than_greedy_box' do
+  let(:items) { [{ dimensions: [5, 5, 5], weight: 30 }, { dimensions: [5, 5, 5], weight: 30 }] }
+
+  it 'ignores weight without a weight limit' do
+    expect(RustPacker.check_container_is_bigger_than_greedy_box({ dimensions: [5, 5, 10] }, items)).to be true
+  end
+
+  it 'respects a weight limit' do
+    expect(RustPacker.check_container_is_bigger_than_greedy_box({ dimensions: [5, 5, 10], weight_limit: 50 }, items)).to be false
+    expect(RustPacker.check_container_is_bigger_than_greedy_box({ dimensions: [5, 5, 10], weight_limit: 60 }, items)).to be true
+  end
 end
 
 describe '.find_smallest_container' do
@@ -364,6 +1029,16 @@ describe '.find_smallest_container' do
       )
       expect(containers).to eq([[30.0, 30.0, 220.0], [20.0, 110.0, 110.0], [30.0, 110.0, 120.0], [30.0, 110.0, 140.0], [40.0, 110.0, 110.0]])
     end
+
+    it 'keeps only the containers within the limits natively' do
+      items = Array.new(3) { { dimensions: [13, 23.5, 48] } }
+      expect(RustPacker.find_smallest_containers(items, 5, [50, 50, 50])).to eq([[26.0, 48.0, 48.0], [36.5, 48.0, 48.0]])
+    end
+
+    it 'falls back to all containers when none is within the limits' do
+      items = Array.new(3) { { dimensions: [13, 23.5, 48] } }
+      expect(RustPacker.find_smallest_containers(items, 1, [10, 10, 10])).to eq([[13.0, 48.0, 71.5]])
+    end
   end
 
   describe 'find smallest with limits' do
@@ -404,3 +1079,24 @@ describe '.find_smallest_container' do
     end
   end
 end
+
+describe '.design_box' do
+  let(:items) { Array.new(3) { { dimensions: [13, 23.5, 48], weight: 1 } } }
+
+  it 'rounds the box up to the increment around the walls' do
+    designed = EasyBoxPacker.design_box(items: items, max_dimensions: [100, 100, 100], increment: 5, wall_thickness: 0.5)
+    expect(designed[:outer_dimensions]).to eq([25.0, 40.0, 50.0])
+    expect(designed[:inner_dimensions]).to eq([24.0, 39.0, 49.0])
+    expect(designed[:cost]).to eq(8500.0)
+    expect(designed[:packing][:placements].length).to eq(3)
+  end
+
+  it 'keeps to the minimum size' do
+    designed = EasyBoxPacker.design_box(items: [{ dimensions: [1, 1, 1] }], max_dimensions: [60, 60, 60], min_dimensions: [10, 10, 5])
+    expect(designed[:outer_dimensions]).to eq([5.0, 10.0, 10.0])
+  end
+
+  it 'returns nil when no box can carry the items' do
+    expect(EasyBoxPacker.design_box(items: items, max_dimensions: [100, 100, 100], weight_limit: 2)).to be_nil
+  end
+end
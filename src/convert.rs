@@ -0,0 +1,379 @@
+//! Reads the hashes handed over from Ruby into the core's types.
+//!
+//! Everything here goes through [`Value`] rather than rutie's objects, so
+//! it can run, and be fuzzed, without a Ruby VM.
+
+use easy_box_packer_core::{BoxSpec, Carrier, Container, ContainerType, Coordinates, Dimensions, Item, Merit, PackError, PackOptions, Packing, Placement, Rate, Rotation, Strategy, WeightLimit};
+
+/// A Ruby object as the conversions read it.
+pub trait Value: Sized {
+    fn is_nil(&self) -> bool;
+
+    /// `Some` for `true` and `false` only.
+    fn boolean(&self) -> Option<bool>;
+
+    /// `Some` for an `Integer` that fits a machine word.
+    fn integer(&self) -> Option<i64>;
+
+    /// `Some` for a `Float` only.
+    fn float(&self) -> Option<f64>;
+
+    fn string(&self) -> Option<String>;
+
+    fn symbol(&self) -> Option<String>;
+
+    fn array(&self) -> Option<Vec<Self>>;
+
+    fn is_hash(&self) -> bool;
+
+    /// The value of a hash under the symbol `key`, nil if it has none.
+    fn at(&self, key: &str) -> Self;
+}
+
+fn to_dimension<V: Value>(value: &V, item: Option<usize>, field: &'static str) -> Result<f64, PackError> {
+    if let Some(i) = value.integer() {
+        return Ok(i as f64);
+    }
+    value.float().ok_or(PackError::WrongType { item, field, expected: "a number" })
+}
+
+fn to_optional_dimension<V: Value>(value: &V, item: Option<usize>, field: &'static str) -> Result<Option<f64>, PackError> {
+    if value.is_nil() {
+        return Ok(None);
+    }
+    to_dimension(value, item, field).map(Some)
+}
+
+pub fn to_coordinates<V: Value>(value: &V, item: Option<usize>, field: &'static str) -> Result<Coordinates, PackError> {
+    if value.is_nil() {
+        return Err(PackError::MissingField { item, field });
+    }
+    let array = value.array().ok_or(PackError::WrongType { item, field, expected: "an Array" })?;
+    if array.len() != 3 {
+        return Err(PackError::WrongArity { item, field, expected: 3, actual: array.len() });
+    }
+    Ok([
+        to_dimension(&array[0], item, field)?,
+        to_dimension(&array[1], item, field)?,
+        to_dimension(&array[2], item, field)?,
+    ])
+}
+
+fn to_dimensions<V: Value>(value: &V, item: Option<usize>) -> Result<Dimensions, PackError> {
+    Ok(Dimensions::from_array(&to_coordinates(value, item, "dimensions")?))
+}
+
+fn check_hash<V: Value>(value: &V, item: Option<usize>) -> Result<(), PackError> {
+    let field = if item.is_some() { "item" } else { "container" };
+    if value.is_hash() {
+        Ok(())
+    } else {
+        Err(PackError::WrongType { item, field, expected: "a Hash" })
+    }
+}
+
+/// An array of rotations, each an array of three indices into the item's
+/// dimensions.
+fn to_rotations<V: Value>(value: &V, item: Option<usize>) -> Result<Vec<Rotation>, PackError> {
+    let field = "allowed_rotations";
+    let expected = "an Array of [Integer, Integer, Integer]";
+    let array = value.array().ok_or(PackError::WrongType { item, field, expected })?;
+    let mut rotations = Vec::with_capacity(array.len());
+    for rotation in array {
+        let rotation = rotation.array().ok_or(PackError::WrongType { item, field, expected })?;
+        if rotation.len() != 3 {
+            return Err(PackError::WrongArity { item, field, expected: 3, actual: rotation.len() });
+        }
+        let mut axes: Rotation = [0; 3];
+        for (axis, index) in rotation.iter().enumerate() {
+            match index.integer() {
+                Some(i) if (0..3).contains(&i) => axes[axis] = i as usize,
+                _ => return Err(PackError::WrongType { item, field, expected }),
+            }
+        }
+        rotations.push(axes);
+    }
+    Ok(rotations)
+}
+
+fn to_flag<V: Value>(hash: &V, key: &str) -> bool {
+    let value = hash.at(key);
+    !value.is_nil() && value.boolean().unwrap_or(true)
+}
+
+/// The item's `:id`, or else its `:sku`, as a string.
+fn to_id<V: Value>(hash: &V, item: Option<usize>) -> Result<Option<String>, PackError> {
+    for field in ["id", "sku"] {
+        let value = hash.at(field);
+        if value.is_nil() {
+            continue;
+        }
+        if let Some(string) = value.string().or_else(|| value.symbol()) {
+            return Ok(Some(string));
+        }
+        if let Some(i) = value.integer() {
+            return Ok(Some(i.to_string()));
+        }
+        return Err(PackError::WrongType { item, field, expected: "a String, Symbol or Integer" });
+    }
+    Ok(None)
+}
+
+/// A non-negative integer, `None` if it is not given.
+fn to_count<V: Value>(value: &V, item: Option<usize>, field: &'static str) -> Result<Option<u64>, PackError> {
+    if value.is_nil() {
+        return Ok(None);
+    }
+    match value.integer() {
+        Some(i) if i >= 0 => Ok(Some(i as u64)),
+        _ => Err(PackError::WrongType { item, field, expected: "a non-negative Integer" }),
+    }
+}
+
+/// The name of a symbol option, `None` if it is not given.
+fn to_choice<V: Value>(hash: &V, key: &'static str, expected: &'static str) -> Result<Option<String>, PackError> {
+    let value = hash.at(key);
+    if value.is_nil() {
+        return Ok(None);
+    }
+    value.symbol().map(Some).ok_or(PackError::WrongType { item: None, field: key, expected })
+}
+
+/// `item` is the index of the item being converted, `None` for the container.
+pub fn to_item<V: Value>(value: &V, item: Option<usize>) -> Result<Item, PackError> {
+    check_hash(value, item)?;
+    let dimensions = to_dimensions(&value.at("dimensions"), item)?;
+    let weight = to_optional_dimension(&value.at("weight"), item, "weight")?;
+    let max_load = to_optional_dimension(&value.at("max_load"), item, "max_load")?;
+    let mut result = Item { dimensions, weight, max_load, ..Item::new([1.0; 3], None) };
+    if let Some(id) = to_id(value, item)? {
+        result = result.with_id(id);
+    }
+    let allowed_rotations = value.at("allowed_rotations");
+    if !allowed_rotations.is_nil() {
+        result = result.with_allowed_rotations(to_rotations(&allowed_rotations, item)?);
+    }
+    if to_flag(value, "keep_upright") {
+        result = result.keep_upright();
+    }
+    // only an explicit false stops stacking
+    if value.at("stackable").boolean() == Some(false) {
+        result = result.not_stackable();
+    }
+    Ok(result)
+}
+
+pub fn to_container<V: Value>(value: &V, item: Option<usize>) -> Result<Container, PackError> {
+    check_hash(value, item)?;
+    let dimensions = to_dimensions(&value.at("dimensions"), item)?;
+    // a missing or zero weight limit means the container takes any weight
+    let weight_limit = match to_optional_dimension(&value.at("weight_limit"), item, "weight_limit")? {
+        None | Some(0.0) => WeightLimit::Unlimited,
+        Some(limit) => WeightLimit::Bounded(limit),
+    };
+    let mut container = Container::new(dimensions.dimensions, weight_limit);
+    // items go inside the walls when the inner size is given
+    let inner_dimensions = value.at("inner_dimensions");
+    if !inner_dimensions.is_nil() {
+        let inner = to_coordinates(&inner_dimensions, item, "inner_dimensions")?;
+        if inner.iter().zip(&dimensions.dimensions).any(|(inner, outer)| inner > outer) {
+            return Err(PackError::WrongType { item, field: "inner_dimensions", expected: "no bigger than :dimensions" });
+        }
+        container.dimensions = Dimensions::from_array(&inner);
+    }
+    if let Some(tare_weight) = to_optional_dimension(&value.at("tare_weight"), item, "tare_weight")? {
+        container = container.with_tare_weight(tare_weight);
+    }
+    Ok(container)
+}
+
+pub fn to_box_spec<V: Value>(value: &V) -> Result<BoxSpec, PackError> {
+    let item = None;
+    if !value.is_hash() {
+        return Err(PackError::WrongType { item, field: "spec", expected: "a Hash" });
+    }
+    let mut spec = BoxSpec::new(to_coordinates(&value.at("max_dimensions"), item, "max_dimensions")?);
+    let min_dimensions = value.at("min_dimensions");
+    if !min_dimensions.is_nil() {
+        spec = spec.with_min_dimensions(to_coordinates(&min_dimensions, item, "min_dimensions")?);
+    }
+    if let Some(increment) = to_optional_dimension(&value.at("increment"), item, "increment")? {
+        spec = spec.with_increment(increment);
+    }
+    if let Some(wall_thickness) = to_optional_dimension(&value.at("wall_thickness"), item, "wall_thickness")? {
+        spec = spec.with_wall_thickness(wall_thickness);
+    }
+    if let Some(limit) = to_optional_dimension(&value.at("weight_limit"), item, "weight_limit")? {
+        spec = spec.with_weight_limit(if limit == 0.0 { WeightLimit::Unlimited } else { WeightLimit::Bounded(limit) });
+    }
+    if let Some(board_cost) = to_optional_dimension(&value.at("board_cost"), item, "board_cost")? {
+        spec.board_cost = board_cost;
+    }
+    if let Some(void_cost) = to_optional_dimension(&value.at("void_cost"), item, "void_cost")? {
+        spec.void_cost = void_cost;
+    }
+    Ok(spec)
+}
+
+pub fn to_carrier<V: Value>(value: &V) -> Result<Carrier, PackError> {
+    let item = None;
+    if !value.is_hash() {
+        return Err(PackError::WrongType { item, field: "carrier", expected: "a Hash" });
+    }
+    let dim_divisor = match to_optional_dimension(&value.at("dim_divisor"), item, "dim_divisor")? {
+        Some(dim_divisor) => dim_divisor,
+        None => return Err(PackError::MissingField { item, field: "dim_divisor" }),
+    };
+    let rates = value.at("rates");
+    if rates.is_nil() {
+        return Ok(Carrier::new(dim_divisor));
+    }
+    let expected = "an Array of { max_weight:, cost: }";
+    let rates = rates.array().ok_or(PackError::WrongType { item, field: "rates", expected })?;
+    let mut result = Vec::with_capacity(rates.len());
+    for rate in rates {
+        if !rate.is_hash() {
+            return Err(PackError::WrongType { item, field: "rates", expected });
+        }
+        let max_weight = to_optional_dimension(&rate.at("max_weight"), item, "max_weight")?;
+        let cost = to_optional_dimension(&rate.at("cost"), item, "cost")?;
+        match (max_weight, cost) {
+            (Some(max_weight), Some(cost)) => result.push(Rate { max_weight, cost }),
+            (None, _) => return Err(PackError::MissingField { item, field: "max_weight" }),
+            (_, None) => return Err(PackError::MissingField { item, field: "cost" }),
+        }
+    }
+    Ok(Carrier::new(dim_divisor).with_rates(result))
+}
+
+fn to_container_type<V: Value>(value: &V) -> Result<ContainerType, PackError> {
+    let item = None;
+    let container = to_container(value, item)?;
+    let cost = match to_optional_dimension(&value.at("cost"), item, "cost")? {
+        Some(cost) => cost,
+        None => return Err(PackError::MissingField { item, field: "cost" }),
+    };
+    let mut container_type = ContainerType::new(container, cost);
+    if let Some(stock) = to_count(&value.at("stock"), item, "stock")? {
+        container_type = container_type.with_stock(stock as usize);
+    }
+    Ok(container_type)
+}
+
+fn to_placement<V: Value>(value: &V) -> Result<Placement, PackError> {
+    let item = None;
+    if !value.is_hash() {
+        return Err(PackError::WrongType { item, field: "placements", expected: "an Array of placement Hashes" });
+    }
+    let extents = to_coordinates(&value.at("dimensions"), item, "dimensions")?;
+    let position = to_coordinates(&value.at("position"), item, "position")?;
+    let weight = to_optional_dimension(&value.at("weight"), item, "weight")?;
+    let mut placed = Item::new(extents, weight);
+    if let Some(id) = to_id(value, item)? {
+        placed = placed.with_id(id);
+    }
+    if let Some(instance) = to_count(&value.at("instance"), item, "instance")? {
+        placed.instance = instance as usize;
+    }
+    // the extents are already along the container's axes
+    Ok(Placement::new(&placed, [0, 1, 2], position))
+}
+
+/// A packing of copies of `container`, as `pack` reports it.
+fn to_packing<V: Value>(value: &V, container: &Container) -> Result<Packing, PackError> {
+    if !value.is_hash() {
+        return Err(PackError::WrongType { item: None, field: "packings", expected: "an Array of packing Hashes" });
+    }
+    let mut packing = Packing::new(container);
+    packing.weight = to_optional_dimension(&value.at("weight"), None, "weight")?.unwrap_or(0.0);
+    let placements = value.at("placements");
+    if placements.is_nil() {
+        return Ok(packing);
+    }
+    let expected = "an Array of placement Hashes";
+    let placements = placements.array().ok_or(PackError::WrongType { item: None, field: "placements", expected })?;
+    for placement in &placements {
+        packing.placements.push(to_placement(placement)?);
+    }
+    Ok(packing)
+}
+
+/// Packings of copies of `container`, as `pack` reports them.
+pub fn to_packings<V: Value>(value: &V, container: &Container) -> Result<Vec<Packing>, PackError> {
+    let packings = value.array().ok_or(PackError::WrongType { item: None, field: "packings", expected: "an Array of packing Hashes" })?;
+    packings.iter().map(|packing| to_packing(packing, container)).collect()
+}
+
+/// Options are optional, anything but a hash means the defaults.
+pub fn to_options<V: Value>(value: &V) -> Result<PackOptions, PackError> {
+    let mut options = PackOptions::default();
+    if !value.is_hash() {
+        return Ok(options);
+    }
+    options.legacy_positions = to_flag(value, "legacy_positions");
+    options.greedy_fallback = value.at("greedy_fallback").is_nil() || to_flag(value, "greedy_fallback");
+    options.given_axes = to_flag(value, "given_axes");
+    options.time_limit_ms = to_count(&value.at("time_limit_ms"), None, "time_limit_ms")?;
+    options.seed = to_count(&value.at("seed"), None, "seed")?.unwrap_or(0);
+    options.node_limit = to_count(&value.at("node_limit"), None, "node_limit")?;
+    options.min_support = to_optional_dimension(&value.at("min_support"), None, "min_support")?;
+    let cog_envelope = value.at("cog_envelope");
+    if !cog_envelope.is_nil() {
+        options.cog_envelope = Some(to_coordinates(&cog_envelope, None, "cog_envelope")?);
+    }
+
+    let expected = "one of :residual_space, :free_volume";
+    let merit = match to_choice(value, "merit", expected)?.as_deref() {
+        None | Some("residual_space") => Merit::ResidualSpace,
+        Some("free_volume") => Merit::FreeVolume,
+        Some(_) => return Err(PackError::WrongType { item: None, field: "merit", expected }),
+    };
+    let expected = "one of :spaces, :extreme_points, :maximal_spaces, :layers, :exact, :greedy_box";
+    options.strategy = match to_choice(value, "strategy", expected)?.as_deref() {
+        None | Some("spaces") => Strategy::Spaces,
+        Some("extreme_points") => Strategy::ExtremePoints(merit),
+        Some("maximal_spaces") => Strategy::MaximalSpaces,
+        Some("layers") => Strategy::Layers,
+        Some("exact") => Strategy::Exact,
+        Some("greedy_box") => Strategy::GreedyBox,
+        Some(_) => return Err(PackError::WrongType { item: None, field: "strategy", expected }),
+    };
+    Ok(options)
+}
+
+fn to_array<V: Value>(value: &V, field: &'static str) -> Result<Vec<V>, PackError> {
+    value.array().ok_or(PackError::WrongType { item: None, field, expected: "an Array" })
+}
+
+pub fn to_container_types<V: Value>(value: &V) -> Result<Vec<ContainerType>, PackError> {
+    to_array(value, "containers")?.iter().map(to_container_type).collect()
+}
+
+pub fn to_containers<V: Value>(value: &V) -> Result<Vec<Container>, PackError> {
+    to_array(value, "containers")?.iter().map(|container| to_container(container, None)).collect()
+}
+
+/// Most copies one item hash may ask for, so a stray quantity cannot run
+/// the process out of memory.
+pub const MAX_QUANTITY: u64 = 10_000;
+
+/// The items, each hash expanded into its `:quantity` of copies.
+pub fn to_items<V: Value>(value: &V) -> Result<Vec<Item>, PackError> {
+    let hashes = to_array(value, "items")?;
+    let mut items: Vec<Item> = Vec::with_capacity(hashes.len());
+    for (i, value) in hashes.iter().enumerate() {
+        let item = to_item(value, Some(i))?;
+        // checked before expanding, so errors point at the hash given
+        item.check(i)?;
+        let quantity = value.at("quantity");
+        if quantity.is_nil() {
+            items.push(item);
+            continue;
+        }
+        match quantity.integer() {
+            Some(quantity) if (1..=MAX_QUANTITY as i64).contains(&quantity) => items.extend(item.copies(quantity as usize)),
+            _ => return Err(PackError::WrongType { item: Some(i), field: "quantity", expected: "an Integer from 1 to 10000" }),
+        }
+    }
+    Ok(items)
+}
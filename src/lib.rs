@@ -1,249 +1,142 @@
-extern crate rutie;
-
-use rutie::{AnyObject, Array, Class, Fixnum, Float, Hash, NilClass, Object, Symbol, Boolean, RString};
-use std::cmp::Ordering;
-use std::ops::Index;
-use core::fmt;
-
-rutie::class!(RustPacker);
-
-type Coordinates = [f64; 3];
-
-fn to_dimension(rb_dimension: &AnyObject) -> f64 {
-    match rb_dimension.try_convert_to::<Fixnum>() {
-        Ok(i) => i.to_i64() as f64,
-        Err(_) => rb_dimension.try_convert_to::<Float>().unwrap().to_f64(),
-    }
-}
-
-trait RubyArrayConvertible {
-    fn from_ruby(array: &Array) -> Self;
-    fn to_ruby(&self) -> Array;
-}
+// rutie::methods! generates extern fns taking the class wrapper by value
+#![allow(improper_ctypes_definitions)]
 
-impl RubyArrayConvertible for Coordinates {
-    fn from_ruby(array: &Array) -> Self {
-        [
-            to_dimension(&array.at(0)),
-            to_dimension(&array.at(1)),
-            to_dimension(&array.at(2)),
-        ]
-    }
-
-    fn to_ruby(&self) -> Array {
-        let mut array = Array::new();
-        array.push(Float::new(self[0]));
-        array.push(Float::new(self[1]));
-        array.push(Float::new(self[2]));
-        array
-    }
-}
+extern crate easy_box_packer_core;
+extern crate rutie;
 
-trait RubyFloatConvertible {
-    fn to_f(&self) -> f64;
-}
+mod convert;
 
-impl RubyFloatConvertible for Option<f64> {
-    fn to_f(&self) -> f64 {
-        match *self {
-            Some(f) => f,
-            None => 0.0
-        }
-    }
-}
-
-fn cmp_coordinates(a: &Coordinates, b: &Coordinates) -> Ordering {
-    if a[0] < b[0] {
-        return Ordering::Less;
-    }
-    if a[0] > b[0] {
-        return Ordering::Greater;
-    }
-    if a[1] < b[1] {
-        return Ordering::Less;
-    }
-    if a[1] > b[1] {
-        return Ordering::Greater;
-    }
-    if a[2] < b[2] {
-        return Ordering::Less;
-    }
-    if a[2] > b[2] {
-        return Ordering::Greater;
-    }
-    Ordering::Equal
-}
+use convert::{to_box_spec, to_carrier, to_container, to_container_types, to_containers, to_coordinates, to_items, to_options, to_packings, Value};
+use easy_box_packer_core::{Container, Coordinates, DesignedBox, Dimensions, Item, LowerBounds, PackError, PackOptions, PackResult, Packing, Placement, Space, Strategy, Unpacked, Violation};
+use rutie::{AnyException, AnyObject, Array, Boolean, Class, Fixnum, Float, Hash, Module, NilClass, Object, RString, Symbol, VM};
 
-#[derive(Clone)]
-struct Dimensions {
-    dimensions: Coordinates,
-    length: f64,
-    width: f64,
-    height: f64
-}
+rutie::class!(RustPacker);
 
-impl Dimensions {
-    fn from_array(array: &Coordinates) -> Dimensions {
-        let mut sorted = array.clone();
-        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        Dimensions {
-            dimensions: *array,
-            length: sorted[2],
-            width: sorted[1],
-            height: sorted[0]
-        }
+impl Value for AnyObject {
+    fn is_nil(&self) -> bool {
+        Object::is_nil(self)
     }
 
-    fn volume(&self) -> f64 {
-        self.length * self.width * self.height
+    fn boolean(&self) -> Option<bool> {
+        self.try_convert_to::<Boolean>().ok().map(|b| b.to_bool())
     }
 
-    fn cmp_lwh(&self, other: &Self) -> Ordering {
-        cmp_coordinates(&[self.length, self.width, self.height], &[other.length, other.width, other.height])
+    fn integer(&self) -> Option<i64> {
+        self.try_convert_to::<Fixnum>().ok().map(|i| i.to_i64())
     }
 
-    fn cmp_hwl(&self, other: &Self) -> Ordering {
-        cmp_coordinates(&[self.height, self.width, self.length], &[other.height, other.width, other.length])
+    fn float(&self) -> Option<f64> {
+        self.try_convert_to::<Float>().ok().map(|f| f.to_f64())
     }
-}
 
-impl RubyArrayConvertible for Dimensions {
-    fn from_ruby(array: &Array) -> Self {
-        Self::from_array(&Coordinates::from_ruby(array))
+    fn string(&self) -> Option<String> {
+        self.try_convert_to::<RString>().ok().map(|s| s.to_string())
     }
 
-    fn to_ruby(&self) -> Array {
-        self.dimensions.to_ruby()
+    fn symbol(&self) -> Option<String> {
+        self.try_convert_to::<Symbol>().ok().map(|s| s.to_string())
     }
-}
-
-impl Index<usize> for Dimensions {
-    type Output = f64;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.dimensions[index]
+    fn array(&self) -> Option<Vec<AnyObject>> {
+        self.try_convert_to::<Array>().ok().map(|array| array.into_iter().collect())
     }
-}
-
-struct Item {
-    dimensions: Dimensions,
-    weight: Option<f64>
-}
 
-impl Item {
-    fn from_ruby(hash: Hash) -> Item {
-        let dimensions = Dimensions::from_ruby(&hash.at(&Symbol::new("dimensions")).try_convert_to::<Array>().unwrap());
-        let weight = to_optional_dimension(&hash.at(&Symbol::new("weight")));
-        Item { dimensions, weight }
+    fn is_hash(&self) -> bool {
+        self.try_convert_to::<Hash>().is_ok()
     }
-}
 
-impl fmt::Display for Item {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let result = write!(f, "{{:dimensions=>[{}, {}, {}]", self.dimensions[0], self.dimensions[1], self.dimensions[2]);
-        if result.is_err() {
-            return result;
+    fn at(&self, key: &str) -> AnyObject {
+        match self.try_convert_to::<Hash>() {
+            Ok(hash) => hash.at(&Symbol::new(key)),
+            Err(_) => NilClass::new().to_any_object(),
         }
-        if let Some(w) = self.weight {
-            let result = write!(f, ", weight: {}", w);
-            if result.is_err() {
-                return result;
-            }
-        }
-        write!(f, "}}")
     }
 }
 
-struct Container {
-    dimensions: Dimensions,
-    weight_limit: Option<f64>
+trait ToRuby<T> {
+    fn to_ruby(&self) -> T;
 }
 
-impl Container {
-    fn from_ruby(hash: Hash) -> Container {
-        let dimensions = Dimensions::from_ruby(&hash.at(&Symbol::new("dimensions")).try_convert_to::<Array>().unwrap());
-        let weight_limit = to_optional_dimension(&hash.at(&Symbol::new("weight_limit")));
-        Container { dimensions, weight_limit }
+impl ToRuby<Array> for Coordinates {
+    fn to_ruby(&self) -> Array {
+        let mut array = Array::new();
+        array.push(Float::new(self[0]));
+        array.push(Float::new(self[1]));
+        array.push(Float::new(self[2]));
+        array
     }
 }
 
-fn to_optional_dimension(rb_dimension: &AnyObject) -> Option<f64> {
-    match rb_dimension.try_convert_to::<NilClass>() {
-        Ok(_) => None,
-        Err(_) => Some(to_dimension(&rb_dimension))
+impl ToRuby<Array> for Dimensions {
+    fn to_ruby(&self) -> Array {
+        self.dimensions.to_ruby()
     }
 }
 
-fn extract_items(rb_array_of_hashes: Array) -> Vec<Item> {
-    let mut items : Vec<Item> = Vec::with_capacity(rb_array_of_hashes.length());
-    for hash in rb_array_of_hashes {
-        items.push(Item::from_ruby(hash.try_convert_to::<Hash>().unwrap()));
-    }
-    items
+/// Options are optional, a missing argument means the defaults.
+fn extract_options(rb_options: Result<AnyObject, AnyException>) -> Result<PackOptions, PackError> {
+    rb_options.map_or_else(|_| Ok(PackOptions::default()), |options| to_options(&options))
 }
 
-
-fn cmp_dimensions_and_position(a: &[Placement; 3], b: &[Placement; 3]) -> Ordering {
-    let ordering = a[0].dimensions.cmp_hwl(&b[0].dimensions);
-    if ordering != Ordering::Equal {
-        return ordering;
-    }
-
-    let ordering = a[1].dimensions.cmp_hwl(&b[1].dimensions);
-    if ordering != Ordering::Equal {
-        return ordering;
-    }
-
-    a[2].dimensions.cmp_hwl(&b[2].dimensions)
+fn extract_container(rb_container: Result<AnyObject, AnyException>) -> Result<Container, PackError> {
+    let rb_container = rb_container.map_err(|_| PackError::MissingField { item: None, field: "container" })?;
+    to_container(&rb_container, None)
 }
 
-struct RotationAndMargin<'a> {
-    rotation: &'a Coordinates,
-    sorted_margins: Coordinates,
+fn extract_items(rb_items: Result<AnyObject, AnyException>) -> Result<Vec<Item>, PackError> {
+    let rb_items = rb_items.map_err(|_| PackError::MissingField { item: None, field: "items" })?;
+    to_items(&rb_items)
 }
 
-#[derive(Clone)]
-struct Space {
-    dimensions: Dimensions,
-    position: Coordinates
+/// Raises `EasyBoxPacker::InvalidInput` carrying the offending item index.
+fn raise_invalid_input(error: PackError) -> ! {
+    let index = match error.item_index() {
+        Some(i) => Fixnum::new(i as i64).to_any_object(),
+        None => NilClass::new().to_any_object(),
+    };
+    let exception = Module::from_existing("EasyBoxPacker")
+        .get_nested_class("InvalidInput")
+        .new_instance(&[RString::new_utf8(&error.to_string()).to_any_object(), index]);
+    VM::raise_ex(AnyException::from(exception.value()));
+    unreachable!()
 }
 
-impl Space {
+impl ToRuby<Hash> for Space {
     fn to_ruby(&self) -> Hash {
         let mut hash = Hash::new();
         hash.store(Symbol::new("dimensions"), self.dimensions.to_ruby());
         hash.store(Symbol::new("position"), self.position.to_ruby());
+        hash.store(Symbol::new("volume"), Float::new(self.volume()));
         hash
     }
 }
 
-#[derive(Clone)]
-struct Placement {
-    dimensions: Dimensions,
-    position: Coordinates,
-    weight: Option<f64>
-}
-
-impl Placement {
+impl ToRuby<Hash> for Placement {
     fn to_ruby(&self) -> Hash {
         let mut hash = Hash::new();
         hash.store(Symbol::new("dimensions"), self.dimensions.to_ruby());
         hash.store(Symbol::new("position"), self.position.to_ruby());
+        let mut rotation = Array::new();
+        for &axis in &self.rotation {
+            rotation.push(Fixnum::new(axis as i64));
+        }
+        hash.store(Symbol::new("rotation"), rotation);
         match self.weight {
             Some(f) => hash.store(Symbol::new("weight"), Float::new(f)),
             None =>    hash.store(Symbol::new("weight"), NilClass::new())
         };
+        hash.store(Symbol::new("load"), Float::new(self.load));
+        hash.store(Symbol::new("support"), Float::new(self.support));
+        match &self.id {
+            Some(id) => hash.store(Symbol::new("id"), RString::new_utf8(id)),
+            None => hash.store(Symbol::new("id"), NilClass::new()),
+        };
+        hash.store(Symbol::new("instance"), Fixnum::new(self.instance as i64));
         hash
     }
 }
 
-struct Packing {
-    placements: Vec<Placement>,
-    spaces: Vec<Space>,
-    weight: f64
-}
-
-impl Packing {
+impl ToRuby<Hash> for Packing {
     fn to_ruby(&self) -> Hash {
         let mut hash = Hash::new();
         let mut placement_array = Array::new();
@@ -255,504 +148,263 @@ impl Packing {
             space_array.push(space.to_ruby());
         }
         hash.store(Symbol::new("placements"), placement_array);
-        hash.store(Symbol::new("position"), space_array);
+        hash.store(Symbol::new("spaces"), space_array);
+        hash.store(Symbol::new("free_volume"), Float::new(self.free_volume()));
         hash.store(Symbol::new("weight"), Float::new(self.weight));
+        hash.store(Symbol::new("gross_weight"), Float::new(self.gross_weight()));
+        hash.store(Symbol::new("outer_dimensions"), self.outer_dimensions.to_ruby());
+        hash.store(Symbol::new("container_type"), Fixnum::new(self.container_type as i64));
+        if let Some(billable_weight) = self.billable_weight {
+            hash.store(Symbol::new("billable_weight"), Float::new(billable_weight));
+        }
+        match self.centre_of_gravity() {
+            Some(centre) => hash.store(Symbol::new("centre_of_gravity"), centre.to_ruby()),
+            None => hash.store(Symbol::new("centre_of_gravity"), NilClass::new()),
+        };
 
         hash
     }
 }
 
-fn internal_item_greedy_box(items: &[Item]) -> Dimensions {
-    let mut max_length : f64 = 0.0;
-    let mut max_width : f64 = 0.0;
-    let mut total_height : f64 = 0.0;
-    for item in items.iter() {
-        max_length = max_length.max(item.dimensions.length);
-        max_width = max_width.max(item.dimensions.width);
-        total_height += item.dimensions.height;
-    }
-    Dimensions::from_array(&[max_length, max_width, 0.1 * (10.0 * total_height).round()])
-}
-
-fn place(item: &Item, space: &Space) -> Option<Placement> {
-    let permutations: [Coordinates; 6] = [
-        [item.dimensions.width,  item.dimensions.height, item.dimensions.length],
-        [item.dimensions.width,  item.dimensions.length, item.dimensions.height],
-        [item.dimensions.height, item.dimensions.width,  item.dimensions.length],
-        [item.dimensions.height, item.dimensions.length, item.dimensions.width],
-        [item.dimensions.length, item.dimensions.width,  item.dimensions.height],
-        [item.dimensions.length, item.dimensions.height, item.dimensions.width],
-    ];
-
-    let mut possible_rotations_and_margins: Vec<RotationAndMargin> = Vec::with_capacity(6);
-
-    for rotation in permutations.iter() {
-        if rotation[0] > space.dimensions[0]
-            || rotation[1] > space.dimensions[1]
-            || rotation[2] > space.dimensions[2]
-        {
-            continue;
-        }
-        let mut sorted_margins = [
-            space.dimensions[0] - rotation[0],
-            space.dimensions[1] - rotation[1],
-            space.dimensions[2] - rotation[2],
-        ];
-        sorted_margins.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        possible_rotations_and_margins.push(RotationAndMargin {
-            rotation,
-            sorted_margins,
-        });
-    }
-
-    if possible_rotations_and_margins.len() == 0 {
-        return None;
+impl ToRuby<Hash> for DesignedBox {
+    fn to_ruby(&self) -> Hash {
+        let mut hash = Hash::new();
+        hash.store(Symbol::new("inner_dimensions"), self.inner.to_ruby());
+        hash.store(Symbol::new("outer_dimensions"), self.outer.to_ruby());
+        hash.store(Symbol::new("cost"), Float::new(self.cost));
+        hash.store(Symbol::new("packing"), self.packing.to_ruby());
+        hash
     }
-
-    possible_rotations_and_margins
-        .sort_by(|a, b| cmp_coordinates(&a.sorted_margins, &b.sorted_margins));
-
-    Some(Placement {
-        dimensions: Dimensions::from_array(&possible_rotations_and_margins[0].rotation),
-        position: space.dimensions.dimensions,
-        weight: item.weight
-    })
-}
-
-fn break_up_space(space: &Space, placement: &Placement) -> [Space; 3] {
-    let mut possible_spaces: [[Placement; 3]; 6] = [
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    space.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    placement.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-        // HEIGHT SPACE => LENGTH => WIDTH
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    space.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    space.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-        // LENGTH SPACE => HEIGHT => WIDTH
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    space.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    space.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-        // LENGTH SPACE => WIDTH  => HEIGHT
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    space.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    placement.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-        // WIDTH SPACE  => LENGTH => HEIGHT
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    placement.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    placement.dimensions[0],
-                    placement.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-        // WIDTH SPACE  => HEIGHT => LENGTH
-        [
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    space.dimensions[1] - placement.dimensions[1],
-                    space.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1] + placement.dimensions[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0],
-                    placement.dimensions[1],
-                    space.dimensions[2] - placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0],
-                    space.position[1],
-                    space.position[2] + placement.dimensions[2],
-                ],
-                weight: Some(0.0)
-            },
-            Placement {
-                dimensions: Dimensions::from_array(&[
-                    space.dimensions[0] - placement.dimensions[0],
-                    placement.dimensions[1],
-                    placement.dimensions[2],
-                ]),
-                position: [
-                    space.position[0] + placement.dimensions[0],
-                    space.position[1],
-                    space.position[2],
-                ],
-                weight: Some(0.0)
-            },
-        ],
-    ];
-
-    // PICK biggest
-    possible_spaces.sort_by(|a, b| cmp_dimensions_and_position(&a, &b));
-    let biggest = &possible_spaces[5];
-    [
-        Space {
-            dimensions: biggest[0].dimensions.clone(),
-            position: biggest[0].position
-        },
-        Space {
-            dimensions: biggest[1].dimensions.clone(),
-            position: biggest[1].position
-        },
-        Space {
-            dimensions: biggest[2].dimensions.clone(),
-            position: biggest[2].position
-        },
-    ]
 }
 
-fn internal_check_container_is_bigger_than_greedy_box(container: &Container, items: &[Item]) -> bool {
-    let greedy_box = internal_item_greedy_box(&items);
-    let mut weight = 0.0;
-    for item in items {
-        weight += item.weight.to_f();
+impl ToRuby<Hash> for LowerBounds {
+    fn to_ruby(&self) -> Hash {
+        let mut hash = Hash::new();
+        hash.store(Symbol::new("volume"), Fixnum::new(self.volume as i64));
+        hash.store(Symbol::new("l1"), Fixnum::new(self.l1 as i64));
+        hash.store(Symbol::new("l2"), Fixnum::new(self.l2 as i64));
+        hash.store(Symbol::new("weight"), Fixnum::new(self.weight as i64));
+        hash.store(Symbol::new("best"), Fixnum::new(self.best() as i64));
+        hash
     }
-    // If container has 0 as weight limit (infinite), box is large enough
-    container.dimensions.length >= greedy_box[0] &&
-        container.dimensions.width >= greedy_box[1]  &&
-        container.dimensions.height >= greedy_box[2] &&
-        (container.weight_limit.to_f() >= weight ||
-         container.weight_limit.to_f() == 0.0)
 }
 
-fn internal_generate_packing_for_greedy_box(items: &[Item]) -> Packing {
-    let mut height = 0.0;
-    let mut weight = 0.0;
-    let mut placements : Vec<Placement> = Vec::with_capacity(items.len());
-    for item in items {
-        let item_weight = item.weight.to_f();
-        weight += item_weight;
-        height += item.dimensions.height;
-        placements.push( Placement { dimensions: item.dimensions.clone(), position: [0.0, 0.0, height], weight: item.weight } );
-    }
-    Packing {
-        placements,
-        spaces: vec![],
-        weight
+impl ToRuby<Hash> for Unpacked {
+    fn to_ruby(&self) -> Hash {
+        let mut hash = Hash::new();
+        match &self.item.id {
+            Some(id) => hash.store(Symbol::new("id"), RString::new_utf8(id)),
+            None => hash.store(Symbol::new("id"), NilClass::new()),
+        };
+        hash.store(Symbol::new("instance"), Fixnum::new(self.item.instance as i64));
+        hash.store(Symbol::new("dimensions"), self.item.dimensions.to_ruby());
+        match self.item.weight {
+            Some(weight) => hash.store(Symbol::new("weight"), Float::new(weight)),
+            None => hash.store(Symbol::new("weight"), NilClass::new()),
+        };
+        hash.store(Symbol::new("reason"), Symbol::new(self.reason.code()));
+        match self.limit {
+            Some(limit) => hash.store(Symbol::new("limit"), Float::new(limit)),
+            None => hash.store(Symbol::new("limit"), NilClass::new()),
+        };
+        hash
     }
 }
 
-rutie::methods!(
-    RustPacker,
-    _itself,
-    fn pack(container: Hash, items: Array) -> Hash {
-        let container = Container::from_ruby(container.unwrap());
-        let mut items = extract_items(items.unwrap());
-        let mut packings: Vec<Packing> = Vec::new();
-        let mut errors: Vec<String> = Vec::new();
-
-        // so by length first (biggest) and then sort in descending order
-        items.sort_by(|a, b| b.dimensions.cmp_lwh(&a.dimensions));
-        for item in &items {
-            if item.weight.to_f() > container.weight_limit.to_f() && container.weight_limit.to_f() != 0.0 {
-                errors.push(format!("Item: {} is too heavy for container", item));
-                continue;
+impl ToRuby<Hash> for Violation {
+    fn to_ruby(&self) -> Hash {
+        let mut hash = Hash::new();
+        hash.store(Symbol::new("violation"), Symbol::new(self.code()));
+        match self.packing() {
+            Some(packing) => hash.store(Symbol::new("packing"), Fixnum::new(packing as i64)),
+            None => hash.store(Symbol::new("packing"), NilClass::new()),
+        };
+        match *self {
+            Violation::OutOfBounds { placement, .. } | Violation::UnknownPlacement { placement, .. } => {
+                hash.store(Symbol::new("placement"), Fixnum::new(placement as i64));
             }
-            let mut item_has_been_packed = false;
-            for packing in &mut packings {
-                // If this packings going to be too big with this
-                // item as well then skip on to the next packing
-                if packing.weight + item.weight.to_f() > container.weight_limit.to_f() {
-                    continue;
-                }
-                // remove volume size = 0 (not possible to pack)
-                packing.spaces.retain(|space| space.dimensions.volume() > 0.0);
-                // try minimum space first
-                packing.spaces.sort_by(|a, b| a.dimensions.cmp_hwl(&b.dimensions));
-                for (i, space) in (&packing.spaces).iter().enumerate() {
-                    // Try placing the item in this space,
-                    // if it doesn't fit skip on the next space
-                    let placement = place(&item, space);
-                    if let Some(p) = placement {
-                        let new_spaces = break_up_space(space, &p);
-                        packing.placements.push(p);
-                        packing.weight += item.weight.to_f();
-                        packing.spaces.remove(i);
-                        packing.spaces.extend_from_slice(&new_spaces);
-                        item_has_been_packed = true;
-                        break;
-                    }
-                    else {
-                        continue;
-                    }
-                }
-                if item_has_been_packed {
-                    break;
-                }
+            Violation::Overlap { placements: (a, b), .. } => {
+                let mut placements = Array::new();
+                placements.push(Fixnum::new(a as i64));
+                placements.push(Fixnum::new(b as i64));
+                hash.store(Symbol::new("placements"), placements);
             }
-            if item_has_been_packed {
-                continue;
+            Violation::Duplicate { placement, item, .. } => {
+                hash.store(Symbol::new("placement"), Fixnum::new(placement as i64));
+                hash.store(Symbol::new("item"), Fixnum::new(item as i64));
             }
-
-            // Can't fit in any of the spaces for the current packings
-            // so lets try a new space the size of the container
-            let space = Space {
-                dimensions: Dimensions::from_array(&[
-                    container.dimensions.length,
-                    container.dimensions.width,
-                    container.dimensions.height
-                ]),
-                position: [0.0, 0.0, 0.0]
-            };
-            let placement = place(&item, &space);
-            // If it can't be placed in this space, then it's just
-            // too big for the container and we should abandon hope
-            match placement {
-                None => {
-                    errors.push(format!("Item: {} cannot be placed in container", item));
-                    continue;
-                },
-                Some(p) => {
-                    // Otherwise lets put the item in a new packing
-                    // and break up the remaing free space around it
-                    let spaces = break_up_space(&space, &p);
-                    packings.push(Packing {
-                        placements: [p].to_vec(),
-                        weight: item.weight.to_f(),
-                        spaces: spaces.to_vec()
-                    });
-                }
+            Violation::Missing { item } => {
+                hash.store(Symbol::new("item"), Fixnum::new(item as i64));
+            }
+            Violation::WeightMismatch { reported, actual, .. } => {
+                hash.store(Symbol::new("reported"), Float::new(reported));
+                hash.store(Symbol::new("actual"), Float::new(actual));
+            }
+            Violation::Overweight { weight, limit, .. } => {
+                hash.store(Symbol::new("weight"), Float::new(weight));
+                hash.store(Symbol::new("limit"), Float::new(limit));
             }
         }
+        hash.store(Symbol::new("message"), RString::new_utf8(&self.to_string()));
+        hash
+    }
+}
 
-        if packings.len() > 1 && internal_check_container_is_bigger_than_greedy_box(&container, &items) {
-            packings.clear();
-            errors.clear();
-            packings.push(internal_generate_packing_for_greedy_box(&items));
-        }
+fn strategy_name(strategy: Strategy) -> &'static str {
+    match strategy {
+        Strategy::Spaces => "spaces",
+        Strategy::ExtremePoints(_) => "extreme_points",
+        Strategy::MaximalSpaces => "maximal_spaces",
+        Strategy::Layers => "layers",
+        Strategy::Exact => "exact",
+        Strategy::GreedyBox => "greedy_box",
+    }
+}
+
+impl ToRuby<Hash> for PackResult {
+    fn to_ruby(&self) -> Hash {
         let mut packing_array = Array::new();
-        for packing in packings {
+        for packing in &self.packings {
             packing_array.push(packing.to_ruby());
         }
         let mut error_array = Array::new();
-        for error in errors {
-            error_array.push(RString::new_utf8(&error));
+        for error in &self.errors {
+            error_array.push(RString::new_utf8(error));
+        }
+
+        let mut unpacked_array = Array::new();
+        for unpacked in &self.unpacked {
+            unpacked_array.push(unpacked.to_ruby());
         }
 
         let mut result = Hash::new();
         result.store(Symbol::new("packings"), packing_array);
         result.store(Symbol::new("errors"), error_array);
+        result.store(Symbol::new("unpacked"), unpacked_array);
+        result.store(Symbol::new("strategy"), Symbol::new(strategy_name(self.strategy)));
+        match (&self.lower_bounds, self.gap()) {
+            (Some(lower_bounds), Some(gap)) => {
+                result.store(Symbol::new("lower_bounds"), lower_bounds.to_ruby());
+                result.store(Symbol::new("gap"), Float::new(gap));
+            }
+            _ => {
+                result.store(Symbol::new("lower_bounds"), NilClass::new());
+                result.store(Symbol::new("gap"), NilClass::new());
+            }
+        };
+        result.store(Symbol::new("proven_optimal"), Boolean::new(self.proven_optimal));
+        if let Some(cost) = self.cost {
+            result.store(Symbol::new("cost"), Float::new(cost));
+        }
         result
     }
+}
+
+rutie::methods!(
+    RustPacker,
+    _itself,
+    fn pack(container: AnyObject, items: AnyObject, options: AnyObject) -> Hash {
+        let result = extract_options(options).and_then(|options| {
+            let container = extract_container(container)?;
+            let items = extract_items(items)?;
+            easy_box_packer_core::pack_with_options(&container, &items, &options)
+        });
+        match result {
+            Ok(result) => result.to_ruby(),
+            Err(error) => raise_invalid_input(error),
+        }
+    }
+
+    fn pack_container_types(types: AnyObject, items: AnyObject, options: AnyObject) -> Hash {
+        let result = extract_options(options).and_then(|options| {
+            let types = types.map_err(|_| PackError::MissingField { item: None, field: "containers" })?;
+            let types = to_container_types(&types)?;
+            let items = extract_items(items)?;
+            easy_box_packer_core::pack_container_types(&types, &items, &options)
+        });
+        match result {
+            Ok(result) => result.to_ruby(),
+            Err(error) => raise_invalid_input(error),
+        }
+    }
 
-    fn item_greedy_box(items: Array) -> Array {
-        let items = items.unwrap();
-        let items = extract_items(items);
-        internal_item_greedy_box(&items).to_ruby()
+    fn pack_for_carrier(containers: AnyObject, carrier: AnyObject, items: AnyObject, options: AnyObject) -> Hash {
+        let result = extract_options(options).and_then(|options| {
+            let containers = containers.map_err(|_| PackError::MissingField { item: None, field: "containers" })?;
+            let containers = to_containers(&containers)?;
+            let carrier = carrier.map_err(|_| PackError::MissingField { item: None, field: "carrier" })?;
+            let carrier = to_carrier(&carrier)?;
+            let items = extract_items(items)?;
+            easy_box_packer_core::pack_for_carrier(&containers, &items, &carrier, &options)
+        });
+        match result {
+            Ok(result) => result.to_ruby(),
+            Err(error) => raise_invalid_input(error),
+        }
     }
 
-    fn check_container_is_bigger_than_greedy_box(container: Hash, items: Array) -> Boolean {
-        let container = Container::from_ruby(container.unwrap());
-        let items = extract_items(items.unwrap());
-        let greedy_box = internal_item_greedy_box(&items);
-        let mut weight = 0.0;
-        for item in items {
-            weight += item.weight.to_f();
+    fn design_box(items: AnyObject, spec: AnyObject, options: AnyObject) -> AnyObject {
+        let result = extract_options(options).and_then(|options| {
+            let spec = spec.map_err(|_| PackError::MissingField { item: None, field: "spec" })?;
+            let spec = to_box_spec(&spec)?;
+            let items = extract_items(items)?;
+            easy_box_packer_core::design_box(&items, &spec, &options)
+        });
+        match result {
+            Ok(Some(designed)) => designed.to_ruby().to_any_object(),
+            Ok(None) => NilClass::new().to_any_object(),
+            Err(error) => raise_invalid_input(error),
+        }
+    }
+
+    fn validate(container: AnyObject, items: AnyObject, packings: AnyObject, options: AnyObject) -> Array {
+        let result = extract_options(options).and_then(|options| {
+            let container = extract_container(container)?;
+            let items = extract_items(items)?;
+            let packings = packings.map_err(|_| PackError::MissingField { item: None, field: "packings" })?;
+            let packings = to_packings(&packings, &container)?;
+            Ok(easy_box_packer_core::validate_with_options(&container, &items, &packings, &options))
+        });
+        let mut violations = Array::new();
+        for violation in result.unwrap_or_else(|e| raise_invalid_input(e)) {
+            violations.push(violation.to_ruby());
         }
-        let result = container.dimensions.length >= greedy_box[0] &&
-            container.dimensions.width >= greedy_box[1]  &&
-            container.dimensions.height >= greedy_box[2] &&
-            (container.weight_limit.to_f() >= weight ||
-            container.weight_limit.to_f() >= 0.0);
+        violations
+    }
+
+    fn item_greedy_box(items: AnyObject) -> Array {
+        let items = extract_items(items).unwrap_or_else(|e| raise_invalid_input(e));
+        easy_box_packer_core::item_greedy_box(&items).to_ruby()
+    }
+
+    fn check_container_is_bigger_than_greedy_box(container: AnyObject, items: AnyObject) -> Boolean {
+        let container = extract_container(container).unwrap_or_else(|e| raise_invalid_input(e));
+        let items = extract_items(items).unwrap_or_else(|e| raise_invalid_input(e));
+        Boolean::new(easy_box_packer_core::check_container_is_bigger_than_greedy_box(&container, &items))
+    }
 
-        Boolean::new(result)
+    fn find_smallest_containers(items: AnyObject, max_count: AnyObject, limit_dimensions: AnyObject) -> Array {
+        let result = extract_items(items).and_then(|items| {
+            let max_count = match max_count.map(|count| count.try_convert_to::<Fixnum>().map(|i| i.to_i64())) {
+                Ok(Ok(i)) if i >= 0 => i as usize,
+                _ => return Err(PackError::WrongType { item: None, field: "max_count", expected: "a non-negative Integer" }),
+            };
+            let limit_dimensions = match limit_dimensions {
+                Ok(limit) if !Object::is_nil(&limit) => Some(to_coordinates(&limit, None, "limit_dimensions")?),
+                _ => None,
+            };
+            easy_box_packer_core::find_smallest_containers(&items, max_count, limit_dimensions)
+        });
+        let mut containers = Array::new();
+        for dimensions in result.unwrap_or_else(|e| raise_invalid_input(e)) {
+            containers.push(dimensions.to_ruby());
+        }
+        containers
     }
 
-    fn generate_packing_for_greedy_box(items: Array) -> Array {
-        let items = extract_items(items.unwrap());
-        let packing = internal_generate_packing_for_greedy_box(&items);
+    fn generate_packing_for_greedy_box(items: AnyObject) -> Array {
+        let items = extract_items(items).unwrap_or_else(|e| raise_invalid_input(e));
+        let packing = easy_box_packer_core::generate_packing_for_greedy_box(&items);
         let mut result = Array::new();
         result.push(packing.to_ruby());
         result
@@ -764,8 +416,13 @@ rutie::methods!(
 pub extern "C" fn Init_rust_packer() {
     Class::new("RustPacker", None).define(|itself| {
         itself.def_self("pack", pack);
+        itself.def_self("pack_container_types", pack_container_types);
+        itself.def_self("pack_for_carrier", pack_for_carrier);
+        itself.def_self("design_box", design_box);
+        itself.def_self("validate", validate);
         itself.def_self("item_greedy_box", item_greedy_box);
         itself.def_self("check_container_is_bigger_than_greedy_box", check_container_is_bigger_than_greedy_box);
         itself.def_self("generate_packing_for_greedy_box", generate_packing_for_greedy_box);
+        itself.def_self("find_smallest_containers", find_smallest_containers);
     });
 }